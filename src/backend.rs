@@ -0,0 +1,155 @@
+//! Pluggable simulation backends
+//!
+//! A [`Backend`] executes a [`Circuit`] and then answers probability and
+//! sampling queries about its output state. The benchmark only talks to
+//! this trait, so the same daily circuit can be run through different
+//! simulation methods and the result records which one produced it.
+//!
+//! Measurement outcomes are `u128` bitstrings with qubit q in bit q, which
+//! leaves room for backends that go beyond what a dense state vector can hold.
+
+use crate::mps::DEFAULT_MAX_BOND_DIM;
+use crate::{Circuit, QuantumSimulator, C64};
+
+/// A simulator that can execute circuits and be measured
+pub trait Backend {
+    /// Short name recorded in benchmark results
+    fn name(&self) -> &'static str;
+
+    /// Number of qubits
+    fn n_qubits(&self) -> usize;
+
+    /// Apply every operation of `circuit` to the current state
+    fn apply_circuit(&mut self, circuit: &Circuit);
+
+    /// Probability of measuring the basis state `outcome`
+    fn probability(&self, outcome: u128) -> f64;
+
+    /// Full output distribution, `None` if the backend cannot produce it
+    fn probabilities(&self) -> Option<Vec<f64>>;
+
+    /// State amplitudes, `None` if the backend does not hold a dense pure state
+    fn amplitudes(&self) -> Option<Vec<C64>>;
+
+    /// Draw `n_shots` measurement outcomes
+    fn sample(&mut self, n_shots: usize) -> Vec<u128>;
+
+    /// Estimated fidelity of the simulated state with the exact one,
+    /// `None` for exact backends
+    fn estimated_fidelity(&self) -> Option<f64> {
+        None
+    }
+
+    /// Estimated fidelity lost in each applied moment, whose product is
+    /// [`estimated_fidelity`](Self::estimated_fidelity); `None` for exact backends
+    fn layer_fidelities(&self) -> Option<Vec<f64>> {
+        None
+    }
+}
+
+/// Available backends, selectable by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackendKind {
+    /// Dense state vector ([`QuantumSimulator`])
+    #[default]
+    StateVector,
+    /// Matrix Product State ([`MpsSimulator`](crate::MpsSimulator)) for 1D
+    /// layouts, truncated to at most `max_bond_dim`
+    Mps { max_bond_dim: usize },
+    /// Exact density matrix ([`DensityMatrixSimulator`](crate::DensityMatrixSimulator))
+    /// for small noisy circuits
+    DensityMatrix,
+}
+
+impl BackendKind {
+    /// All backends, in the order they are listed to users
+    pub const ALL: [BackendKind; 3] = [
+        BackendKind::StateVector,
+        BackendKind::Mps { max_bond_dim: DEFAULT_MAX_BOND_DIM },
+        BackendKind::DensityMatrix,
+    ];
+
+    /// Name used on the command line and in results
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackendKind::StateVector => "statevector",
+            BackendKind::Mps { .. } => "mps",
+            BackendKind::DensityMatrix => "densitymatrix",
+        }
+    }
+
+    /// Largest qubit count the backend handles
+    pub fn max_qubits(&self) -> usize {
+        match self {
+            BackendKind::StateVector => 20,
+            BackendKind::Mps { .. } => 100,
+            BackendKind::DensityMatrix => 12,
+        }
+    }
+
+    /// Look up a backend by its name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+impl Backend for QuantumSimulator {
+    fn name(&self) -> &'static str {
+        BackendKind::StateVector.name()
+    }
+
+    fn n_qubits(&self) -> usize {
+        QuantumSimulator::n_qubits(self)
+    }
+
+    fn apply_circuit(&mut self, circuit: &Circuit) {
+        QuantumSimulator::apply_circuit(self, circuit);
+    }
+
+    fn probability(&self, outcome: u128) -> f64 {
+        self.state[outcome as usize].norm_sqr()
+    }
+
+    fn probabilities(&self) -> Option<Vec<f64>> {
+        Some(QuantumSimulator::probabilities(self))
+    }
+
+    fn amplitudes(&self) -> Option<Vec<C64>> {
+        Some(self.state.iter().copied().collect())
+    }
+
+    fn sample(&mut self, n_shots: usize) -> Vec<u128> {
+        QuantumSimulator::sample(self, n_shots)
+            .into_iter()
+            .map(|s| s as u128)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_names_roundtrip() {
+        for kind in BackendKind::ALL {
+            assert_eq!(BackendKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(BackendKind::from_name("nonsense"), None);
+    }
+
+    #[test]
+    fn test_statevector_backend_matches_simulator() {
+        let mut circuit = Circuit::new(2);
+        let mut layer = crate::Moment::new();
+        layer.push(crate::Gate::H, vec![0]);
+        circuit.push_moment(layer);
+
+        let mut sim = QuantumSimulator::with_seed(2, 1);
+        let backend: &mut dyn Backend = &mut sim;
+        backend.apply_circuit(&circuit);
+        assert!((backend.probability(1) - 0.5).abs() < 1e-12);
+        assert_eq!(backend.probabilities().unwrap().len(), 4);
+        assert!(backend.sample(64).iter().all(|&s| s == 0 || s == 1));
+    }
+}
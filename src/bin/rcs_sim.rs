@@ -1,26 +1,253 @@
 //! RCS Simulator Binary
 //! 
-//! Usage: rcs_sim <depth> <n_qubits> [samples]
+//! Usage: rcs_sim [sweep] <depth> <n_qubits> [samples] [flags]
+//!        rcs_sim qv <n_qubits> [samples] [flags]
+//!        rcs_sim score <samples-file> (--circuit <path.json> | <depth> <n_qubits> --seed <u64>)
 //! Output: JSON result to stdout
+//!
+//! `rcs_sim --help` prints the flag reference of [`usage`].
 
-use quantum_rcs::run_benchmark;
+use quantum_rcs::noise::{
+    CoherentErrors, ReadoutError, Relaxation, WhiteNoise, SYCAMORE_GATE_TIME_1Q, SYCAMORE_GATE_TIME_2Q,
+};
+use quantum_rcs::porter_thomas::HISTOGRAM_BIN_WIDTH;
+use quantum_rcs::qv::{HOG_CONFIDENCE_Z, HOG_THRESHOLD};
+use quantum_rcs::score::parse_samples;
+use quantum_rcs::{
+    generate_rcs_circuit, run_depth_sweep, run_quantum_volume, score_samples, try_run_benchmark_with_config,
+    BackendKind, Circuit, Estimator, Gate, NoiseModel, PorterThomasReport, RcsConfig,
+};
 use std::env;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
+
+/// Flags that set part of the noise model, which a `--noise-model` file replaces
+const NOISE_FLAGS: [&str; 14] = [
+    "--depol-1q", "--depol-2q", "--t1", "--t2", "--gate-time-1q", "--gate-time-2q",
+    "--readout-01", "--readout-10", "--cz-phase", "--cz-phase-spread", "--z-phase", "--z-phase-spread",
+    "--fidelity", "--gate-fidelities",
+];
+
+/// Flags of RCS runs that quantum volume circuits have no use for
+const QV_UNSUPPORTED_FLAGS: [&str; 8] = [
+    "--entangler", "--estimator", "--bootstrap", "--porter-thomas",
+    "--cz-phase", "--cz-phase-spread", "--z-phase", "--z-phase-spread",
+];
+
+/// Parse the value following a `--flag`, exiting with an error if it is missing or invalid
+fn flag_value<'a, T: FromStr>(iter: &mut impl Iterator<Item = &'a String>, flag: &str) -> T {
+    iter.next().and_then(|s| s.parse().ok()).unwrap_or_else(|| {
+        eprintln!("Error: {} requires a valid value", flag);
+        std::process::exit(1);
+    })
+}
+
+/// Command-line reference printed by `--help` and on invalid invocations
+fn usage(program: &str) -> String {
+    format!(
+        "\
+Usage: {program} [sweep] <depth> <n_qubits> [samples] [flags]
+       {program} qv <n_qubits> [samples] [flags]
+       {program} score <samples-file> (--circuit <path.json> | <depth> <n_qubits> --seed <u64>) [flags]
+
+Runs a random circuit sampling benchmark and prints the JSON result to stdout.
+
+Seeds and circuit:
+  --seed <u64>             derive the circuit and sampling seeds from one seed
+  --circuit-seed <u64>     circuit seed; reuse a result's seeds to replay its run exactly
+  --sampling-seed <u64>    sampling seed; changing only this re-samples the same circuit
+  --circuit-out <path>     write the executed circuit as JSON (single-instance runs only)
+  --entangler <gate>       cz (default), fsim (Sycamore's fSim(π/2, π/6)), iswap, sqrt-iswap
+
+Simulation:
+  --threads <n>            kernel threads, 0 = automatic, 1 = serial; results do not depend on it
+  --backend <name>         statevector (default, ≤ 20 qubits), mps (≤ 100), densitymatrix (≤ 12)
+  --max-bond <n>           MPS bond dimension cap (default 64); needs --backend mps
+
+Noise (scored against the noiseless circuit):
+  --depol-1q <p>           Pauli error probability after single-qubit gates
+  --depol-2q <p>           Pauli error probability after two-qubit gates
+  --trajectories <n>       state-vector trajectories the noisy shots are drawn from (default 64);
+                           the density matrix applies noise exactly and adds the expected XEB
+  --t1 <ns>[,<ns>...]      amplitude damping during every moment, one time or one per qubit
+  --t2 <ns>[,<ns>...]      dephasing time (default 2·T1, no pure dephasing)
+  --gate-time-1q <ns>      single-qubit gate duration (default 25 ns)
+  --gate-time-2q <ns>      two-qubit gate duration (default 12 ns)
+  --readout-01 <p>[,...]   probability of reading 0 as 1; adds the mitigated score (≤ 20 qubits)
+  --readout-10 <p>[,...]   probability of reading 1 as 0
+  --cz-phase <rad>         run every CZ as CPhase(π + δ)
+  --cz-phase-spread <rad>  draw δ per pair within ± spread of the offset, fixed by the circuit seed
+  --z-phase <rad>          stray Z rotation after every CZ
+  --z-phase-spread <rad>   draw the Z phases per pair within ± spread of the offset
+  --noise-model <path>     JSON or TOML device profile in the NoiseModel form; replaces the
+                           other noise flags and records the file's SHA-256
+  --fidelity <F>           white noise: each shot is ideal with probability F, uniform otherwise
+  --gate-fidelities <f1q>,<f2q>,<fro>
+                           white noise with F the product of gate and readout fidelities
+
+Scoring:
+  --estimator <name>       linear (default), normalized, log, cross-entropy-difference;
+                           normalized and cross-entropy-difference need ≤ 20 qubits
+  --bootstrap <n>          add a 95% percentile bootstrap interval from n resamples
+  --instances <k>          average over k circuits, the first from the given seeds
+                           (qv default 100)
+  --parallel-instances     run the instances concurrently
+  --porter-thomas          compare the ideal distribution with Porter-Thomas and search the
+                           depth at which the layout anticoncentrates
+
+sweep <depths>             a range 5-14 or a list 4,8,12, each depth on its own circuit;
+                           fits XEB(d) = A·f^d and reports the per-cycle fidelity f
+qv <n_qubits>              quantum volume: square circuits of Haar-random SU(4) layers, passing
+                           if the heavy-output probability exceeds 2/3 by two standard errors;
+                           takes the noise flags but not the coherent phases, --entangler,
+                           --estimator, --bootstrap or --porter-thomas
+score <samples-file>       score measured bitstrings, one per line with qubit 0 first and an
+                           optional count, or a .json object of counts by bitstring;
+                           --reverse-bits reads qubit 0 last, as Qiskit prints counts
+
+Examples:
+  {program} 7 10
+  {program} sweep 5-14 10
+  {program} qv 6 200 --instances 100
+  {program} score shots.txt 7 10 --seed 42
+"
+    )
+}
+
+/// Subcommand selected by the first argument
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Run,
+    Sweep,
+    QuantumVolume,
+}
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let raw_args: Vec<String> = env::args().collect();
+    let mode = match raw_args.get(1).map(String::as_str) {
+        Some("sweep") => Mode::Sweep,
+        Some("qv") => Mode::QuantumVolume,
+        Some("score") => return score_main(&raw_args),
+        Some("--help" | "-h") => {
+            print!("{}", usage(&raw_args[0]));
+            return;
+        }
+        _ => Mode::Run,
+    };
+    let sweep = mode == Mode::Sweep;
     
+    // Split `--flag value` options from positional arguments
+    let mut args = vec![raw_args[0].clone()];
+    let mut seed: Option<u64> = None;
+    let mut circuit_seed: Option<u64> = None;
+    let mut sampling_seed: Option<u64> = None;
+    let mut circuit_out: Option<String> = None;
+    let mut entangler = Gate::CZ;
+    let mut threads = 0;
+    let mut backend = BackendKind::default();
+    let mut max_bond: Option<usize> = None;
+    let mut depol_1q = 0.0;
+    let mut depol_2q = 0.0;
+    let mut trajectories: Option<usize> = None;
+    let mut t1: Option<String> = None;
+    let mut t2: Option<String> = None;
+    let mut gate_time_1q: Option<f64> = None;
+    let mut gate_time_2q: Option<f64> = None;
+    let mut readout_01: Option<String> = None;
+    let mut readout_10: Option<String> = None;
+    let mut coherent = CoherentErrors::default();
+    let mut noise_file: Option<String> = None;
+    let mut white_noise: Option<WhiteNoise> = None;
+    let mut bootstrap: Option<usize> = None;
+    let mut estimator = Estimator::default();
+    let mut n_instances: Option<usize> = None;
+    let mut parallel_instances = false;
+    let mut porter_thomas = false;
+    let mut noise_flags: Vec<&str> = Vec::new();
+    let mut qv_unsupported: Vec<&str> = Vec::new();
+    let mut iter = raw_args.iter().skip(if mode == Mode::Run { 1 } else { 2 });
+    while let Some(arg) = iter.next() {
+        if NOISE_FLAGS.contains(&arg.as_str()) {
+            noise_flags.push(arg);
+        }
+        if QV_UNSUPPORTED_FLAGS.contains(&arg.as_str()) {
+            qv_unsupported.push(arg);
+        }
+        match arg.as_str() {
+            "--seed" => seed = Some(flag_value(&mut iter, "--seed")),
+            "--circuit-seed" => circuit_seed = Some(flag_value(&mut iter, "--circuit-seed")),
+            "--sampling-seed" => sampling_seed = Some(flag_value(&mut iter, "--sampling-seed")),
+            "--circuit-out" => circuit_out = Some(flag_value(&mut iter, "--circuit-out")),
+            "--threads" => threads = flag_value(&mut iter, "--threads"),
+            "--max-bond" => max_bond = Some(flag_value(&mut iter, "--max-bond")),
+            "--depol-1q" => depol_1q = flag_value(&mut iter, "--depol-1q"),
+            "--depol-2q" => depol_2q = flag_value(&mut iter, "--depol-2q"),
+            "--trajectories" => trajectories = Some(flag_value(&mut iter, "--trajectories")),
+            "--t1" => t1 = Some(flag_value(&mut iter, "--t1")),
+            "--t2" => t2 = Some(flag_value(&mut iter, "--t2")),
+            "--gate-time-1q" => gate_time_1q = Some(flag_value(&mut iter, "--gate-time-1q")),
+            "--gate-time-2q" => gate_time_2q = Some(flag_value(&mut iter, "--gate-time-2q")),
+            "--readout-01" => readout_01 = Some(flag_value(&mut iter, "--readout-01")),
+            "--readout-10" => readout_10 = Some(flag_value(&mut iter, "--readout-10")),
+            "--cz-phase" => coherent.cphase_offset = flag_value(&mut iter, "--cz-phase"),
+            "--cz-phase-spread" => coherent.cphase_spread = flag_value(&mut iter, "--cz-phase-spread"),
+            "--z-phase" => coherent.z_offset = flag_value(&mut iter, "--z-phase"),
+            "--z-phase-spread" => coherent.z_spread = flag_value(&mut iter, "--z-phase-spread"),
+            "--bootstrap" => bootstrap = Some(flag_value(&mut iter, "--bootstrap")),
+            "--instances" => n_instances = Some(flag_value(&mut iter, "--instances")),
+            "--parallel-instances" => parallel_instances = true,
+            "--porter-thomas" => porter_thomas = true,
+            "--noise-model" => noise_file = Some(flag_value(&mut iter, "--noise-model")),
+            "--fidelity" => white_noise = Some(WhiteNoise::Fidelity(flag_value(&mut iter, "--fidelity"))),
+            "--gate-fidelities" => {
+                let value: String = flag_value(&mut iter, "--gate-fidelities");
+                white_noise = match per_qubit_values(&value, 3, "--gate-fidelities")[..] {
+                    [one_qubit, two_qubit, readout] => Some(WhiteNoise::GateFidelities { one_qubit, two_qubit, readout }),
+                    _ => unreachable!("per_qubit_values returns one value per entry"),
+                };
+            }
+            "--backend" => {
+                let name: String = flag_value(&mut iter, "--backend");
+                backend = BackendKind::from_name(&name).unwrap_or_else(|| {
+                    let names: Vec<_> = BackendKind::ALL.iter().map(|k| k.name()).collect();
+                    eprintln!("Error: --backend must be one of {}", names.join(", "));
+                    std::process::exit(1);
+                });
+            }
+            "--estimator" => {
+                let name: String = flag_value(&mut iter, "--estimator");
+                estimator = Estimator::from_name(&name).unwrap_or_else(|| {
+                    let names: Vec<_> = Estimator::ALL.iter().map(|e| e.name()).collect();
+                    eprintln!("Error: --estimator must be one of {}", names.join(", "));
+                    std::process::exit(1);
+                });
+            }
+            "--entangler" => entangler = entangler_value(&mut iter),
+            _ => args.push(arg.clone()),
+        }
+    }
+    
+    // Quantum volume circuits are square, so `qv` takes no depth
+    if mode == Mode::QuantumVolume && args.len() > 1 {
+        args.insert(1, args[1].clone());
+    }
     if args.len() < 3 {
-        eprintln!("Usage: {} <depth> <n_qubits> [samples]", args[0]);
-        eprintln!("Example: {} 7 10", args[0]);
+        eprint!("{}", usage(&args[0]));
         std::process::exit(1);
     }
     
-    let depth: usize = args[1].parse().unwrap_or_else(|_| {
-        eprintln!("Error: depth must be a positive integer");
-        std::process::exit(1);
-    });
+    let depths: Vec<usize> = match sweep {
+        true => parse_depths(&args[1]).unwrap_or_else(|| {
+            eprintln!("Error: sweep depths must be a range like 5-14 or a list like 4,8,12");
+            std::process::exit(1);
+        }),
+        false => vec![args[1].parse().unwrap_or_else(|_| {
+            eprintln!("Error: depth must be a positive integer");
+            std::process::exit(1);
+        })],
+    };
+    let depth = depths[0];
     
     let n_qubits: usize = args[2].parse().unwrap_or_else(|_| {
         eprintln!("Error: n_qubits must be a positive integer");
@@ -32,42 +259,577 @@ fn main() {
         .unwrap_or(1024);
     
     // Validate inputs
-    if depth == 0 || depth > 50 {
+    if depths.iter().any(|&d| d == 0 || d > 50) {
         eprintln!("Error: depth must be between 1 and 50");
         std::process::exit(1);
     }
+    if sweep && depths.len() < 2 {
+        eprintln!("Error: a sweep needs at least two depths");
+        std::process::exit(1);
+    }
+    if mode != Mode::Run && circuit_out.is_some() {
+        eprintln!("Error: --circuit-out is only available for single runs");
+        std::process::exit(1);
+    }
+    let n_instances = n_instances.unwrap_or(if mode == Mode::QuantumVolume { 100 } else { 1 });
+    if n_instances > 1 && circuit_out.is_some() {
+        eprintln!("Error: --circuit-out writes one circuit; multi-instance circuits go to results/circuits");
+        std::process::exit(1);
+    }
+    if mode == Mode::QuantumVolume && backend != BackendKind::StateVector {
+        eprintln!("Error: quantum volume runs use the statevector backend");
+        std::process::exit(1);
+    }
+    if let Some(flag) = qv_unsupported.first().filter(|_| mode == Mode::QuantumVolume) {
+        eprintln!("Error: {} does not apply to quantum volume runs", flag);
+        std::process::exit(1);
+    }
+    
+    if max_bond.is_some() && !matches!(backend, BackendKind::Mps { .. }) {
+        eprintln!("Error: --max-bond requires --backend mps");
+        std::process::exit(1);
+    }
+    if let (BackendKind::Mps { max_bond_dim }, Some(bond)) = (&mut backend, max_bond) {
+        if bond == 0 {
+            eprintln!("Error: --max-bond must be at least 1");
+            std::process::exit(1);
+        }
+        *max_bond_dim = bond;
+    }
     
-    if !(2..=20).contains(&n_qubits) {
-        eprintln!("Error: n_qubits must be between 2 and 20");
+    if !(2..=backend.max_qubits()).contains(&n_qubits) {
+        eprintln!("Error: n_qubits must be between 2 and {} for the {} backend",
+            backend.max_qubits(), backend.name());
         std::process::exit(1);
     }
     
-    eprintln!("🔬 Running RCS Benchmark");
-    eprintln!("   Depth: {}", depth);
+    let mut noise = NoiseModel { depolarizing_1q: depol_1q, depolarizing_2q: depol_2q, ..NoiseModel::default() };
+    match (&t1, &t2) {
+        (Some(t1), t2) => {
+            let t1 = per_qubit_values(t1, n_qubits, "--t1");
+            let t2 = match t2 {
+                Some(t2) => per_qubit_values(t2, n_qubits, "--t2"),
+                None => t1.iter().map(|t| 2.0 * t).collect(),
+            };
+            noise = noise.with_relaxation(Relaxation {
+                t1,
+                t2,
+                gate_time_1q: gate_time_1q.unwrap_or(SYCAMORE_GATE_TIME_1Q),
+                gate_time_2q: gate_time_2q.unwrap_or(SYCAMORE_GATE_TIME_2Q),
+            });
+        }
+        (None, Some(_)) => {
+            eprintln!("Error: --t2 requires --t1");
+            std::process::exit(1);
+        }
+        (None, None) => {}
+    }
+    if readout_01.is_some() || readout_10.is_some() {
+        let per_qubit = |value: &Option<String>, flag| match value {
+            Some(v) => per_qubit_values(v, n_qubits, flag),
+            None => vec![0.0; n_qubits],
+        };
+        noise = noise.with_readout(ReadoutError {
+            p01: per_qubit(&readout_01, "--readout-01"),
+            p10: per_qubit(&readout_10, "--readout-10"),
+        });
+    }
+    if coherent != CoherentErrors::default() {
+        noise = noise.with_coherent(coherent);
+    }
+    noise.white_noise = white_noise;
+    let mut noise_sha256 = None;
+    if let Some(path) = &noise_file {
+        if let Some(flag) = noise_flags.first() {
+            eprintln!("Error: --noise-model cannot be combined with {}", flag);
+            std::process::exit(1);
+        }
+        let (model, hash) = NoiseModel::from_file(Path::new(path)).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+        noise = model;
+        noise_sha256 = Some(hash);
+    }
+    if let Err(e) = noise.validate(n_qubits) {
+        eprintln!("Error: invalid noise model: {}", e);
+        std::process::exit(1);
+    }
+    
+    // Trajectories always run on the state vector
+    let sv_max = BackendKind::StateVector.max_qubits();
+    if noise.has_circuit_noise() && n_qubits > sv_max {
+        eprintln!("Error: noisy runs are limited to {} qubits", sv_max);
+        std::process::exit(1);
+    }
+    if trajectories == Some(0) {
+        eprintln!("Error: --trajectories must be at least 1");
+        std::process::exit(1);
+    }
+    if estimator.needs_distribution() && n_qubits > BackendKind::StateVector.max_qubits() {
+        eprintln!("Error: the {} estimator is limited to {} qubits", estimator.name(), BackendKind::StateVector.max_qubits());
+        std::process::exit(1);
+    }
+    // Mitigation inverts the confusion matrix on the dense histogram
+    if noise.readout.is_some() && n_qubits > sv_max {
+        eprintln!("Warning: readout mitigation is limited to {} qubits; only the raw score is reported", sv_max);
+    }
+    if porter_thomas && n_qubits > BackendKind::StateVector.max_qubits() {
+        eprintln!("Error: --porter-thomas is limited to {} qubits", BackendKind::StateVector.max_qubits());
+        std::process::exit(1);
+    }
+    if bootstrap == Some(0) {
+        eprintln!("Error: --bootstrap must be at least 1");
+        std::process::exit(1);
+    }
+    if n_instances == 0 {
+        eprintln!("Error: --instances must be at least 1");
+        std::process::exit(1);
+    }
+    
+    match mode {
+        Mode::Run => {
+            eprintln!("🔬 Running RCS Benchmark");
+            eprintln!("   Depth: {}", depth);
+        }
+        Mode::Sweep => {
+            eprintln!("🔬 Running RCS Depth Sweep");
+            eprintln!("   Depths: {:?}", depths);
+        }
+        Mode::QuantumVolume => eprintln!("🔬 Running Quantum Volume Benchmark"),
+    }
     eprintln!("   Qubits: {}", n_qubits);
     eprintln!("   Samples: {}", samples);
+    if mode != Mode::QuantumVolume {
+        eprintln!("   Entangler: {:?}", entangler);
+    }
+    if n_instances > 1 {
+        eprintln!("   Instances: {}{}", n_instances, if parallel_instances { " (parallel)" } else { "" });
+    }
+    eprintln!("   Backend: {}", backend.name());
+    if let BackendKind::Mps { max_bond_dim } = backend {
+        eprintln!("   Max bond dimension: {}", max_bond_dim);
+    }
+    if let (Some(path), Some(hash)) = (&noise_file, &noise_sha256) {
+        eprintln!("   Noise model: {} (sha256 {})", path, &hash[..12]);
+    }
+    if noise.depolarizing_1q > 0.0 || noise.depolarizing_2q > 0.0 {
+        eprintln!("   Depolarizing noise: p1 = {}, p2 = {}", noise.depolarizing_1q, noise.depolarizing_2q);
+    }
+    if !noise.qubit_depolarizing.is_empty() {
+        eprintln!("   Per-qubit p1: {}", describe_values(&noise.qubit_depolarizing));
+    }
+    if !noise.pairs.is_empty() {
+        let crosstalk = noise.crosstalk().count();
+        eprintln!("   Per-pair noise: {} pairs, {} with ZZ crosstalk", noise.pairs.len(), crosstalk);
+    }
+    if let Some(relaxation) = &noise.relaxation {
+        eprintln!("   Relaxation: T1 = {} ns, T2 = {} ns", describe_values(&relaxation.t1), describe_values(&relaxation.t2));
+    }
+    if let Some(c) = &noise.coherent {
+        eprintln!("   CZ phase error: {} ± {} rad, stray Z: {} ± {} rad",
+            c.cphase_offset, c.cphase_spread, c.z_offset, c.z_spread);
+    }
+    match noise.white_noise {
+        Some(WhiteNoise::Fidelity(f)) => eprintln!("   White noise: F = {}", f),
+        Some(WhiteNoise::GateFidelities { one_qubit, two_qubit, readout }) => {
+            eprintln!("   White noise: gate fidelities 1q = {}, 2q = {}, readout = {}", one_qubit, two_qubit, readout);
+        }
+        None => {}
+    }
+    if let Some(readout) = &noise.readout {
+        eprintln!("   Readout errors: p01 = {}, p10 = {}", describe_values(&readout.p01), describe_values(&readout.p10));
+    }
     eprintln!();
     
+    // Explicit per-stream seeds override the ones derived from --seed
+    let mut config = match seed {
+        Some(seed) => RcsConfig::seeded(depth, n_qubits, samples, seed),
+        None => RcsConfig::new(depth, n_qubits, samples),
+    };
+    if let Some(s) = circuit_seed {
+        config.circuit_seed = s;
+    }
+    if let Some(s) = sampling_seed {
+        config.sampling_seed = s;
+    }
+    config.entangler = entangler;
+    config.threads = threads;
+    config.backend = backend;
+    config.noise = noise;
+    config.noise_sha256 = noise_sha256;
+    config.bootstrap = bootstrap;
+    config.estimator = estimator;
+    config.n_instances = n_instances;
+    config.parallel_instances = parallel_instances;
+    config.porter_thomas = porter_thomas;
+    if let Some(n) = trajectories {
+        config.trajectories = n;
+    }
+    
+    match mode {
+        Mode::Sweep => return run_sweep(&config, &depths),
+        Mode::QuantumVolume => return run_qv(&config),
+        Mode::Run => {}
+    }
+    
     // Run benchmark
-    let result = run_benchmark(depth, n_qubits, samples);
+    let result = try_run_benchmark_with_config(&config).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
     
     eprintln!("✅ Complete!");
-    eprintln!("   XEB Score: {:.4}", result.xeb_score);
+    match result.xeb_std_error {
+        Some(error) => eprintln!("   XEB Score ({}): {:.4} ± {:.4}", estimator.name(), result.xeb_score, error),
+        None => eprintln!("   XEB Score ({}): {:.4}", estimator.name(), result.xeb_score),
+    }
+    for (name, estimate) in result.estimates.iter().filter(|(name, _)| *name != estimator.name()) {
+        match estimate.std_error {
+            Some(error) => eprintln!("   {}: {:.4} ± {:.4}", name, estimate.value, error),
+            None => eprintln!("   {}: {:.4}", name, estimate.value),
+        }
+    }
+    if let Some(summary) = &result.instance_summary {
+        eprintln!("   Instances: median {:.4}, std dev {:.4}, range [{:.4}, {:.4}]",
+            summary.median, summary.std_dev, summary.min, summary.max);
+    }
+    if let Some([lo, hi]) = result.xeb_bootstrap_ci {
+        eprintln!("   95% bootstrap CI: [{:.4}, {:.4}]", lo, hi);
+    }
+    if let Some(mitigated) = result.mitigated_xeb {
+        eprintln!("   Mitigated XEB ({}): {:.4}", estimator.name(), mitigated);
+    }
+    if let Some(fidelity) = result.estimated_fidelity {
+        eprintln!("   Estimated fidelity: {:.4}", fidelity);
+    }
+    if let Some(layers) = &result.layer_fidelities {
+        let layers: Vec<String> = layers.iter().map(|f| format!("{:.4}", f)).collect();
+        eprintln!("   Layer fidelities: {}", layers.join(", "));
+    }
+    if let Some(fidelity) = result.target_fidelity {
+        eprintln!("   Target fidelity: {:.4}", fidelity);
+    }
+    if let Some(fidelity) = result.predicted_fidelity {
+        eprintln!("   Predicted fidelity (digital error model): {:.4}", fidelity);
+    }
+    if let Some(expected) = result.expected_xeb {
+        eprintln!("   Expected XEB (exact): {:.4}", expected);
+    }
+    if let Some(report) = &result.porter_thomas {
+        print_porter_thomas(report, result.anticoncentration_depth, depth);
+    }
     eprintln!("   Runtime: {}ms", result.runtime_ms);
+    if let Some(seed) = config.seed {
+        eprintln!("   Seed: {}", seed);
+    }
+    eprintln!("   Circuit seed: {}", config.circuit_seed);
+    eprintln!("   Sampling seed: {}", config.sampling_seed);
     
     // Output JSON
     let json = serde_json::to_string_pretty(&result).unwrap();
     println!("{}", json);
     
+    // Regenerate the executed circuits from their seeds for storage
+    let circuit_json = |index: usize| serde_json::to_string_pretty(&generate_rcs_circuit(&config.instance(index))).unwrap();
+    if let Some(path) = circuit_out {
+        save(&path, &circuit_json(0));
+    }
+    
     // Also save to results directory if it exists
     let results_dir = Path::new("results");
     if results_dir.exists() {
-        let filename = format!("results/{}.json", 
-            chrono::Utc::now().format("%Y%m%d"));
-        if let Err(e) = fs::write(&filename, &json) {
-            eprintln!("Warning: Could not write to {}: {}", filename, e);
-        } else {
-            eprintln!("   Saved to: {}", filename);
+        let stamp = chrono::Utc::now().format("%Y%m%d");
+        save(&format!("results/{}.json", stamp), &json);
+        
+        // Circuits live in a subdirectory so readme_gen only sees results;
+        // each instance gets its own file so `score --circuit` can read it
+        if fs::create_dir_all("results/circuits").is_ok() {
+            if config.n_instances == 1 {
+                save(&format!("results/circuits/{}.json", stamp), &circuit_json(0));
+            } else {
+                for index in 0..config.n_instances {
+                    save(&format!("results/circuits/{}-{}.json", stamp, index), &circuit_json(index));
+                }
+            }
+        }
+    }
+}
+
+/// Report the Porter-Thomas diagnostics and warn about shallow circuits
+fn print_porter_thomas(report: &PorterThomasReport, anticoncentration_depth: Option<usize>, depth: usize) {
+    eprintln!("   Collision probability 2ⁿΣp²: {:.4} (Porter-Thomas {:.4})",
+        report.collision, report.porter_thomas_collision);
+    eprintln!("   KS test vs Exp(1): D = {:.4}, p = {:.3}", report.ks_statistic, report.ks_p_value);
+    eprintln!("   Histogram of 2ⁿ·p (observed / Porter-Thomas):");
+    let last = report.histogram.len() - 1;
+    for (b, (count, expected)) in report.histogram.iter().zip(&report.expected).enumerate() {
+        let lo = b as f64 * HISTOGRAM_BIN_WIDTH;
+        let label = match b {
+            b if b == last => format!("≥{:.1}", lo),
+            _ => format!("{:.1}-{:.1}", lo, lo + HISTOGRAM_BIN_WIDTH),
+        };
+        eprintln!("     {:>8}: {:>8} / {:>10.1}", label, count, expected);
+    }
+    match anticoncentration_depth {
+        Some(d) if depth < d => {
+            eprintln!("   ⚠️  Depth {} is below the anticoncentration depth {}; XEB is not a fidelity here", depth, d);
         }
+        Some(d) => eprintln!("   Anticoncentration depth: {}", d),
+        None => eprintln!("   ⚠️  No anticoncentration up to depth 50"),
+    }
+}
+
+/// Run a depth sweep, report its fit and store the sweep JSON
+fn run_sweep(config: &RcsConfig, depths: &[usize]) {
+    let sweep = run_depth_sweep(config, depths).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    
+    eprintln!("✅ Complete!");
+    for point in &sweep.points {
+        match point.xeb_std_error {
+            Some(error) => eprintln!("   Depth {:>2}: {:.4} ± {:.4}", point.depth, point.xeb_score, error),
+            None => eprintln!("   Depth {:>2}: {:.4}", point.depth, point.xeb_score),
+        }
+    }
+    match &sweep.fit {
+        Some(fit) => {
+            let error = |e: Option<f64>| e.map(|e| format!(" ± {:.4}", e)).unwrap_or_default();
+            eprintln!("   Per-cycle fidelity f: {:.4}{}", fit.fidelity, error(fit.fidelity_error));
+            eprintln!("   Amplitude A: {:.4}{}", fit.amplitude, error(fit.amplitude_error));
+            if let Some(chi) = fit.reduced_chi_squared {
+                eprintln!("   Reduced χ²: {:.2} ({} points)", chi, fit.n_points);
+            }
+        }
+        None => eprintln!("   No fit: fewer than two depths scored above zero"),
+    }
+    if config.porter_thomas {
+        match sweep.anticoncentration_depth {
+            Some(d) => eprintln!("   Anticoncentration depth: {}", d),
+            None => eprintln!("   ⚠️  No anticoncentration up to depth 50"),
+        }
+    }
+    eprintln!("   Runtime: {}ms", sweep.runtime_ms);
+    eprintln!("   Circuit seed: {}", config.circuit_seed);
+    eprintln!("   Sampling seed: {}", config.sampling_seed);
+    
+    let json = serde_json::to_string_pretty(&sweep).unwrap();
+    println!("{}", json);
+    
+    // Sweeps live in a subdirectory so readme_gen only sees single results
+    if Path::new("results").exists() && fs::create_dir_all("results/sweeps").is_ok() {
+        let stamp = chrono::Utc::now().format("%Y%m%d");
+        save(&format!("results/sweeps/{}.json", stamp), &json);
+    }
+}
+
+/// Score a file of measured bitstrings against the ideal circuit
+fn score_main(raw_args: &[String]) {
+    let mut args = Vec::new();
+    let mut circuit_path: Option<String> = None;
+    let mut seed: Option<u64> = None;
+    let mut circuit_seed: Option<u64> = None;
+    let mut entangler = Gate::CZ;
+    let mut reverse_bits = false;
+    let mut bootstrap: Option<usize> = None;
+    let mut threads = 0;
+    let mut iter = raw_args.iter().skip(2);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--circuit" => circuit_path = Some(flag_value(&mut iter, "--circuit")),
+            "--seed" => seed = Some(flag_value(&mut iter, "--seed")),
+            "--circuit-seed" => circuit_seed = Some(flag_value(&mut iter, "--circuit-seed")),
+            "--entangler" => entangler = entangler_value(&mut iter),
+            "--reverse-bits" => reverse_bits = true,
+            "--bootstrap" => bootstrap = Some(flag_value(&mut iter, "--bootstrap")),
+            "--threads" => threads = flag_value(&mut iter, "--threads"),
+            _ => args.push(arg.as_str()),
+        }
+    }
+    
+    let (circuit, generated_from) = match (&args[..], &circuit_path) {
+        ([_], Some(path)) => {
+            let contents = fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("Error: cannot read {}: {}", path, e);
+                std::process::exit(1);
+            });
+            let circuit: Circuit = serde_json::from_str(&contents).unwrap_or_else(|e| {
+                eprintln!("Error: invalid circuit JSON in {}: {}", path, e);
+                std::process::exit(1);
+            });
+            if let Err(e) = circuit.validate() {
+                eprintln!("Error: invalid circuit in {}: {}", path, e);
+                std::process::exit(1);
+            }
+            (circuit, None)
+        }
+        ([_, depth, n_qubits], None) => {
+            let (Ok(depth), Ok(n_qubits)) = (depth.parse(), n_qubits.parse()) else {
+                eprintln!("Error: depth and n_qubits must be positive integers");
+                std::process::exit(1);
+            };
+            if !(1..=50).contains(&depth) {
+                eprintln!("Error: depth must be between 1 and 50");
+                std::process::exit(1);
+            }
+            if !(2..=BackendKind::StateVector.max_qubits()).contains(&n_qubits) {
+                eprintln!("Error: n_qubits must be between 2 and {}", BackendKind::StateVector.max_qubits());
+                std::process::exit(1);
+            }
+            let mut config = match seed {
+                Some(seed) => RcsConfig::seeded(depth, n_qubits, 0, seed),
+                None => RcsConfig::new(depth, n_qubits, 0),
+            };
+            match circuit_seed {
+                Some(s) => config.circuit_seed = s,
+                None if seed.is_none() => {
+                    eprintln!("Error: a generated circuit needs --seed or --circuit-seed");
+                    std::process::exit(1);
+                }
+                None => {}
+            }
+            config.entangler = entangler;
+            (generate_rcs_circuit(&config), Some(config.circuit_seed))
+        }
+        _ => {
+            eprint!("{}", usage(&raw_args[0]));
+            std::process::exit(1);
+        }
+    };
+    let n_qubits = circuit.n_qubits;
+    if !(1..=BackendKind::StateVector.max_qubits()).contains(&n_qubits) {
+        eprintln!("Error: scoring is limited to {} qubits", BackendKind::StateVector.max_qubits());
+        std::process::exit(1);
+    }
+    if bootstrap == Some(0) {
+        eprintln!("Error: --bootstrap must be at least 1");
+        std::process::exit(1);
+    }
+    
+    let samples_path = args[0];
+    let contents = fs::read_to_string(samples_path).unwrap_or_else(|e| {
+        eprintln!("Error: cannot read {}: {}", samples_path, e);
+        std::process::exit(1);
+    });
+    let json = Path::new(samples_path).extension().is_some_and(|e| e == "json");
+    let samples = parse_samples(&contents, json, n_qubits, reverse_bits).unwrap_or_else(|e| {
+        eprintln!("Error: {}: {}", samples_path, e);
+        std::process::exit(1);
+    });
+    
+    eprintln!("🔬 Scoring Measured Samples");
+    eprintln!("   Qubits: {}", n_qubits);
+    eprintln!("   Moments: {}", circuit.moments.len());
+    eprintln!("   Samples: {}", samples.iter().map(|&(_, count)| count).sum::<u64>());
+    eprintln!();
+    
+    let mut result = score_samples(&circuit, &samples, threads, bootstrap, seed.unwrap_or_default())
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+    result.circuit_seed = generated_from;
+    
+    eprintln!("✅ Complete!");
+    for (name, estimate) in &result.estimates {
+        let ci = result.bootstrap_ci.get(name).map(|[lo, hi]| format!(", 95% CI [{:.4}, {:.4}]", lo, hi));
+        match estimate.std_error {
+            Some(error) => eprintln!("   {}: {:.4} ± {:.4}{}", name, estimate.value, error, ci.unwrap_or_default()),
+            None => eprintln!("   {}: {:.4}{}", name, estimate.value, ci.unwrap_or_default()),
+        }
+    }
+    eprintln!("   Ideal sampling would score (linear): {:.4}", result.ideal_xeb);
+    
+    println!("{}", serde_json::to_string_pretty(&result).unwrap());
+}
+
+/// Parse the gate following `--entangler`, exiting with an error if it is unknown
+fn entangler_value<'a>(iter: &mut impl Iterator<Item = &'a String>) -> Gate {
+    let name: String = flag_value(iter, "--entangler");
+    match name.as_str() {
+        "cz" => Gate::CZ,
+        "fsim" => Gate::SYCAMORE_FSIM,
+        "iswap" => Gate::ISwap,
+        "sqrt-iswap" => Gate::SqrtISwap,
+        _ => {
+            eprintln!("Error: --entangler must be one of cz, fsim, iswap, sqrt-iswap");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Run the quantum volume protocol, report it and store the result JSON
+fn run_qv(config: &RcsConfig) {
+    let result = run_quantum_volume(config);
+    
+    eprintln!("✅ Complete!");
+    eprintln!("   Heavy-output probability: {:.4} ± {:.4} (ideal {:.4})",
+        result.heavy_output_probability, result.heavy_output_std_error, result.ideal_heavy_output_probability);
+    eprintln!("   Lower bound ({}σ): {:.4} vs threshold {:.4}",
+        HOG_CONFIDENCE_Z, result.heavy_output_lower_bound, HOG_THRESHOLD);
+    match (result.passed, result.quantum_volume) {
+        (true, Some(qv)) => eprintln!("   ✅ Passed: quantum volume {}", qv),
+        (true, None) => eprintln!("   ✅ Passed heavy output generation (not square, no quantum volume)"),
+        (false, _) => eprintln!("   ❌ Failed heavy output generation"),
+    }
+    eprintln!("   XEB Score (linear): {:.4}", result.xeb_score);
+    eprintln!("   Runtime: {}ms", result.runtime_ms);
+    eprintln!("   Circuit seed: {}", config.circuit_seed);
+    eprintln!("   Sampling seed: {}", config.sampling_seed);
+    
+    let json = serde_json::to_string_pretty(&result).unwrap();
+    println!("{}", json);
+    
+    if Path::new("results").exists() && fs::create_dir_all("results/qv").is_ok() {
+        let stamp = chrono::Utc::now().format("%Y%m%d");
+        save(&format!("results/qv/{}.json", stamp), &json);
+    }
+}
+
+/// Parse sweep depths given as a range `a-b` or a list `a,b,c`
+fn parse_depths(value: &str) -> Option<Vec<usize>> {
+    match value.split_once('-') {
+        Some((lo, hi)) => {
+            let (lo, hi): (usize, usize) = (lo.trim().parse().ok()?, hi.trim().parse().ok()?);
+            (lo <= hi).then(|| (lo..=hi).collect())
+        }
+        None => value.split(',').map(|t| t.trim().parse().ok()).collect(),
+    }
+}
+
+/// Parse one value for all qubits or a comma-separated value per qubit
+fn per_qubit_values(value: &str, n_qubits: usize, flag: &str) -> Vec<f64> {
+    let values: Vec<f64> = value.split(',').map(|t| t.trim().parse()).collect::<Result<_, _>>()
+        .unwrap_or_else(|_| {
+            eprintln!("Error: {} requires numeric values", flag);
+            std::process::exit(1);
+        });
+    match values.len() {
+        1 => vec![values[0]; n_qubits],
+        n if n == n_qubits => values,
+        n => {
+            eprintln!("Error: {} got {} values for {} qubits", flag, n, n_qubits);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Show a single value if all qubits share it, the full list otherwise
+fn describe_values(values: &[f64]) -> String {
+    if values.windows(2).all(|w| w[0] == w[1]) {
+        values.first().map(|t| t.to_string()).unwrap_or_default()
+    } else {
+        format!("{:?}", values)
+    }
+}
+
+/// Write `contents` to `filename`, reporting but not failing on errors
+fn save(filename: &str, contents: &str) {
+    if let Err(e) = fs::write(filename, contents) {
+        eprintln!("Warning: Could not write to {}: {}", filename, e);
+    } else {
+        eprintln!("   Saved to: {}", filename);
     }
 }
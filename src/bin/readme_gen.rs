@@ -299,6 +299,19 @@ fn generate_readme(results: &[RcsResult]) -> String {
     
     md.push_str("The noisy computer has lower XEB (0.14 vs 0.5), correctly reflecting its degraded performance.\n\n");
     
+    md.push_str("### Step 7: Normalized XEB\n\n");
+    md.push_str("The perfect computer above only scores 0.5: the formula assumes Porter-Thomas statistics, ");
+    md.push_str("which small or shallow circuits do not follow. Dividing by the score of ideal sampling, ");
+    md.push_str("`2ⁿ × Σ p_ideal² - 1`, fixes this for any circuit:\n\n");
+    md.push_str("```\n");
+    md.push_str("Σ p_ideal² = 0.45² + 0.05² + 0.10² + 0.40² = 0.375\n");
+    md.push_str("2² × 0.375 - 1 = 0.5\n");
+    md.push('\n');
+    md.push_str("Normalized XEB (perfect) = 0.5  / 0.5 = 1.0\n");
+    md.push_str("Normalized XEB (noisy)   = 0.14 / 0.5 = 0.28\n");
+    md.push_str("```\n\n");
+    md.push_str("Each result records this `normalized` estimate next to the raw one.\n\n");
+    
     // ===========================================
     // CIRCUIT ARCHITECTURE
     // ===========================================
@@ -313,8 +326,10 @@ fn generate_readme(results: &[RcsResult]) -> String {
     md.push_str("| **H** | `1/√2 [[1,1],[1,-1]]` | Hadamard — creates superposition |\n");
     md.push_str("| **√X** | `½[[1+i,1-i],[1-i,1+i]]` | Square root of X (Pauli) |\n");
     md.push_str("| **√Y** | `½[[1+i,-1-i],[1+i,1+i]]` | Square root of Y (Pauli) |\n");
-    md.push_str("| **√W** | `½[[1+i,-i],[-i,1+i]]` | Square root of W = (X+Y)/√2 |\n");
-    md.push_str("| **CZ** | `diag(1,1,1,-1)` | Controlled-Z — entangles qubits |\n\n");
+    md.push_str("| **√W** | `½[[1+i,-√2·i],[√2,1+i]]` | Square root of W = (X+Y)/√2 |\n");
+    md.push_str("| **CZ** | `diag(1,1,1,-1)` | Controlled-Z — entangles qubits |\n");
+    md.push_str("| **CPhase(φ)** | `diag(1,1,1,e^(iφ))` | Controlled phase — CZ is CPhase(π); CPhase(π+δ) models a miscalibrated CZ |\n");
+    md.push_str("| **fSim(θ,φ)** | `[[1,0,0,0],[0,cos θ,-i sin θ,0],[0,-i sin θ,cos θ,0],[0,0,0,e^(-iφ)]]` | Sycamore's native entangler at θ=π/2, φ=π/6 (`--entangler fsim`) |\n\n");
     
     md.push_str("### Circuit Structure\n\n");
     md.push_str("```\n");
@@ -353,9 +368,13 @@ fn generate_readme(results: &[RcsResult]) -> String {
         
         let display_results: Vec<_> = results.iter().rev().take(30).collect();
         for r in display_results.iter().rev() {
+            let score = match r.xeb_std_error {
+                Some(error) => format!("{:.4} ± {:.4}", r.xeb_score, error),
+                None => format!("{:.4}", r.xeb_score),
+            };
             md.push_str(&format!(
-                "| {} | {} | {} | {:.4} | {} | {}ms |\n",
-                r.date, r.depth, r.qubits, r.xeb_score, r.samples, r.runtime_ms
+                "| {} | {} | {} | {} | {} | {}ms |\n",
+                r.date, r.depth, r.qubits, score, r.samples, r.runtime_ms
             ));
         }
         md.push('\n');
@@ -366,6 +385,16 @@ fn generate_readme(results: &[RcsResult]) -> String {
             md.push_str(&generate_ascii_chart(results));
             md.push_str("```\n\n");
         }
+        
+        if results.iter().any(|r| r.predicted_fidelity.is_some()) {
+            md.push_str("### Predicted vs Measured XEB\n\n");
+            md.push_str("Noisy runs compare the measured XEB with the digital error model, ");
+            md.push_str("the product of all gate and readout fidelities. Points on the diagonal ");
+            md.push_str("match the prediction.\n\n");
+            md.push_str("```\n");
+            md.push_str(&generate_prediction_chart(results));
+            md.push_str("```\n\n");
+        }
     }
     
     // ===========================================
@@ -387,6 +416,10 @@ fn generate_readme(results: &[RcsResult]) -> String {
     md.push_str("./target/release/rcs_sim 7 10\n\n");
     md.push_str("# Custom parameters\n");
     md.push_str("./target/release/rcs_sim 12 8 2048\n\n");
+    md.push_str("# Re-run a past result exactly from its recorded seeds\n");
+    md.push_str("./target/release/rcs_sim 7 10 1024 --circuit-seed 42 --sampling-seed 7\n\n");
+    md.push_str("# Re-sample the same circuit with a new sampling seed\n");
+    md.push_str("./target/release/rcs_sim 7 10 1024 --circuit-seed 42 --sampling-seed 8\n\n");
     md.push_str("# Using cargo\n");
     md.push_str("cargo run --release --bin rcs_sim -- 7 10\n");
     md.push_str("```\n\n");
@@ -399,7 +432,10 @@ fn generate_readme(results: &[RcsResult]) -> String {
     md.push_str("  \"qubits\": 10,\n");
     md.push_str("  \"xeb_score\": 0.8234,\n");
     md.push_str("  \"samples\": 1024,\n");
-    md.push_str("  \"runtime_ms\": 5\n");
+    md.push_str("  \"runtime_ms\": 5,\n");
+    md.push_str("  \"circuit_seed\": 42,\n");
+    md.push_str("  \"sampling_seed\": 7,\n");
+    md.push_str("  \"xeb_std_error\": 0.0312\n");
     md.push_str("}\n");
     md.push_str("```\n\n");
     
@@ -492,7 +528,7 @@ fn generate_ascii_chart(results: &[RcsResult]) -> String {
     let max_score = scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
     
     let range = (max_score - min_score).max(0.1);
-    let chart_min = (min_score - range * 0.1).max(-0.5);
+    let chart_min = min_score - range * 0.1;
     let chart_max = max_score + range * 0.1;
     let chart_range = chart_max - chart_min;
     
@@ -552,3 +588,43 @@ fn generate_ascii_chart(results: &[RcsResult]) -> String {
     
     chart
 }
+
+/// Scatter plot of measured XEB (y) against the digital error model's
+/// predicted fidelity (x), with the diagonal for reference
+fn generate_prediction_chart(results: &[RcsResult]) -> String {
+    let points: Vec<(f64, f64)> = results
+        .iter()
+        .filter_map(|r| r.predicted_fidelity.map(|p| (p, r.xeb_score)))
+        .collect();
+    
+    let lo = points.iter().map(|&(p, x)| p.min(x)).fold(f64::INFINITY, f64::min).min(0.0);
+    let hi = points.iter().map(|&(p, x)| p.max(x)).fold(f64::NEG_INFINITY, f64::max).max(lo + 0.1);
+    let range = hi - lo;
+    
+    let height = 12;
+    let width = 36;
+    let cell = |value: f64, cells: usize| (((value - lo) / range) * (cells - 1) as f64).round() as usize;
+    
+    let mut grid = vec![vec![' '; width]; height];
+    for (col, x) in (0..width).map(|c| (c, lo + c as f64 / (width - 1) as f64 * range)) {
+        grid[cell(x, height)][col] = '·';
+    }
+    for &(predicted, measured) in &points {
+        grid[cell(measured, height)][cell(predicted, width)] = '●';
+    }
+    
+    let mut chart = String::new();
+    for (row, line) in grid.iter().enumerate().rev() {
+        let y_val = lo + row as f64 / (height - 1) as f64 * range;
+        chart.push_str(&format!("{:>6.3} │", y_val));
+        chart.extend(line.iter());
+        chart.push('\n');
+    }
+    chart.push_str("       └");
+    chart.push_str(&"─".repeat(width));
+    chart.push('\n');
+    chart.push_str(&format!("        {:<w$.3}{:>6.3}\n", lo, hi, w = width - 6));
+    chart.push_str(&format!("\n       x = predicted fidelity   y = measured XEB   ({} runs)\n", points.len()));
+    
+    chart
+}
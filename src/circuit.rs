@@ -0,0 +1,272 @@
+//! Circuit intermediate representation
+//!
+//! A [`Circuit`] is an ordered list of [`Moment`]s, each holding the typed
+//! gate operations of one layer. Generators build circuits, simulators
+//! execute them, and the serialized form can be stored next to a benchmark
+//! result to inspect, diff or replay the exact circuit that produced it.
+
+use crate::gates::{self, Matrix2, Matrix4};
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_6};
+use serde::{Deserialize, Serialize};
+
+/// Gate type of an operation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Gate {
+    /// Hadamard
+    H,
+    /// Pauli X
+    X,
+    /// Pauli Y
+    Y,
+    /// Pauli Z
+    Z,
+    /// Phase gate √Z
+    S,
+    /// π/8 gate √S
+    T,
+    /// Rotation about X by the given angle
+    Rx(f64),
+    /// Rotation about Y by the given angle
+    Ry(f64),
+    /// Rotation about Z by the given angle
+    Rz(f64),
+    /// General single-qubit gate U3(θ, φ, λ)
+    U3(f64, f64, f64),
+    /// Square root of X
+    SqrtX,
+    /// Square root of Y
+    SqrtY,
+    /// Square root of W = (X+Y)/√2
+    SqrtW,
+    /// Controlled-Z
+    CZ,
+    /// Controlled phase e^(iφ) on |11⟩; CPhase(π) is CZ
+    CPhase(f64),
+    /// Controlled-NOT, first operand is the control
+    CNOT,
+    /// SWAP
+    SWAP,
+    /// iSWAP
+    ISwap,
+    /// Square root of iSWAP
+    SqrtISwap,
+    /// Fermionic simulation gate fSim(θ, φ)
+    FSim(f64, f64),
+    /// Haar-random two-qubit unitary drawn from the given seed
+    RandomSU4(u64),
+}
+
+impl Gate {
+    /// Sycamore's native entangler fSim(π/2, π/6)
+    pub const SYCAMORE_FSIM: Gate = Gate::FSim(FRAC_PI_2, FRAC_PI_6);
+
+    /// Number of qubits the gate acts on
+    pub fn arity(&self) -> usize {
+        match self {
+            Gate::CZ
+            | Gate::CPhase(_)
+            | Gate::CNOT
+            | Gate::SWAP
+            | Gate::ISwap
+            | Gate::SqrtISwap
+            | Gate::FSim(..)
+            | Gate::RandomSU4(_) => 2,
+            _ => 1,
+        }
+    }
+
+    /// Whether the gate is a Z rotation, which hardware applies as a
+    /// zero-duration frame update
+    pub fn is_virtual(&self) -> bool {
+        matches!(self, Gate::Z | Gate::S | Gate::T | Gate::Rz(_))
+    }
+
+    /// Unitary of a single-qubit gate, `None` for multi-qubit gates
+    pub fn matrix_1q(&self) -> Option<Matrix2> {
+        let m = match *self {
+            Gate::H => gates::h(),
+            Gate::X => gates::x(),
+            Gate::Y => gates::y(),
+            Gate::Z => gates::z(),
+            Gate::S => gates::s(),
+            Gate::T => gates::t(),
+            Gate::Rx(theta) => gates::rx(theta),
+            Gate::Ry(theta) => gates::ry(theta),
+            Gate::Rz(theta) => gates::rz(theta),
+            Gate::U3(theta, phi, lambda) => gates::u3(theta, phi, lambda),
+            Gate::SqrtX => gates::sqrt_x(),
+            Gate::SqrtY => gates::sqrt_y(),
+            Gate::SqrtW => gates::sqrt_w(),
+            Gate::CZ
+            | Gate::CPhase(_)
+            | Gate::CNOT
+            | Gate::SWAP
+            | Gate::ISwap
+            | Gate::SqrtISwap
+            | Gate::FSim(..)
+            | Gate::RandomSU4(_) => return None,
+        };
+        Some(m)
+    }
+
+    /// Unitary of a two-qubit gate, `None` for single-qubit gates
+    pub fn matrix_2q(&self) -> Option<Matrix4> {
+        let m = match *self {
+            Gate::CZ => gates::cz(),
+            Gate::CPhase(phi) => gates::cphase(phi),
+            Gate::CNOT => gates::cnot(),
+            Gate::SWAP => gates::swap(),
+            Gate::ISwap => gates::iswap(),
+            Gate::SqrtISwap => gates::sqrt_iswap(),
+            Gate::FSim(theta, phi) => gates::fsim(theta, phi),
+            Gate::RandomSU4(seed) => gates::haar_unitary_4(seed),
+            _ => return None,
+        };
+        Some(m)
+    }
+}
+
+/// A gate applied to specific qubits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Operation {
+    pub gate: Gate,
+    pub qubits: Vec<usize>,
+}
+
+impl Operation {
+    /// Create an operation, checking that the operand count matches the gate
+    pub fn new(gate: Gate, qubits: Vec<usize>) -> Self {
+        assert_eq!(
+            qubits.len(),
+            gate.arity(),
+            "{:?} acts on {} qubit(s), got {:?}",
+            gate,
+            gate.arity(),
+            qubits
+        );
+        Self { gate, qubits }
+    }
+}
+
+/// One layer of operations, applied in order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Moment {
+    pub operations: Vec<Operation>,
+}
+
+impl Moment {
+    /// Create an empty moment
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an operation to the moment
+    pub fn push(&mut self, gate: Gate, qubits: Vec<usize>) {
+        self.operations.push(Operation::new(gate, qubits));
+    }
+}
+
+/// A quantum circuit on a fixed number of qubits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Circuit {
+    pub n_qubits: usize,
+    pub moments: Vec<Moment>,
+}
+
+impl Circuit {
+    /// Create an empty circuit on `n_qubits` qubits
+    pub fn new(n_qubits: usize) -> Self {
+        Self {
+            n_qubits,
+            moments: Vec::new(),
+        }
+    }
+
+    /// Append a moment, checking that every operand is in range
+    pub fn push_moment(&mut self, moment: Moment) {
+        for op in &moment.operations {
+            assert!(
+                op.qubits.iter().all(|&q| q < self.n_qubits),
+                "operation {:?} out of range for {} qubits",
+                op,
+                self.n_qubits
+            );
+        }
+        self.moments.push(moment);
+    }
+
+    /// Iterate over all operations in execution order
+    pub fn operations(&self) -> impl Iterator<Item = &Operation> {
+        self.moments.iter().flat_map(|m| m.operations.iter())
+    }
+
+    /// Total number of operations
+    pub fn num_operations(&self) -> usize {
+        self.moments.iter().map(|m| m.operations.len()).sum()
+    }
+
+    /// Check what [`Operation::new`] and [`Circuit::push_moment`] assert, for
+    /// circuits that were deserialized rather than built: every operation has
+    /// as many distinct, in-range operands as its gate acts on
+    pub fn validate(&self) -> Result<(), String> {
+        for (index, moment) in self.moments.iter().enumerate() {
+            for op in &moment.operations {
+                if op.qubits.len() != op.gate.arity() {
+                    return Err(format!(
+                        "moment {}: {:?} acts on {} qubit(s), got {:?}",
+                        index,
+                        op.gate,
+                        op.gate.arity(),
+                        op.qubits
+                    ));
+                }
+                if let Some(q) = op.qubits.iter().find(|&&q| q >= self.n_qubits) {
+                    return Err(format!("moment {}: qubit {} out of range for {} qubits", index, q, self.n_qubits));
+                }
+                if op.qubits.len() == 2 && op.qubits[0] == op.qubits[1] {
+                    return Err(format!("moment {}: {:?} acts twice on qubit {}", index, op.gate, op.qubits[0]));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_json_roundtrip() {
+        let mut circuit = Circuit::new(3);
+        let mut layer = Moment::new();
+        layer.push(Gate::H, vec![0]);
+        layer.push(Gate::SqrtW, vec![1]);
+        layer.push(Gate::U3(0.1, 0.2, 0.3), vec![2]);
+        circuit.push_moment(layer);
+        let mut layer = Moment::new();
+        layer.push(Gate::CZ, vec![0, 1]);
+        layer.push(Gate::SYCAMORE_FSIM, vec![1, 2]);
+        circuit.push_moment(layer);
+
+        let json = serde_json::to_string(&circuit).unwrap();
+        let parsed: Circuit = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, circuit);
+        assert_eq!(parsed.num_operations(), 5);
+    }
+
+    #[test]
+    fn test_validate_deserialized_circuit() {
+        let parse = |json: &str| serde_json::from_str::<Circuit>(json).unwrap().validate();
+        assert!(parse(r#"{"n_qubits":2,"moments":[{"operations":[{"gate":"CZ","qubits":[0,1]}]}]}"#).is_ok());
+        assert!(parse(r#"{"n_qubits":2,"moments":[{"operations":[{"gate":"CZ","qubits":[0]}]}]}"#).is_err());
+        assert!(parse(r#"{"n_qubits":2,"moments":[{"operations":[{"gate":"H","qubits":[2]}]}]}"#).is_err());
+        assert!(parse(r#"{"n_qubits":2,"moments":[{"operations":[{"gate":"CZ","qubits":[1,1]}]}]}"#).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_operation_arity_checked() {
+        Operation::new(Gate::CZ, vec![0]);
+    }
+}
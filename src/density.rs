@@ -0,0 +1,297 @@
+//! Density-matrix simulator for small noisy circuits
+//!
+//! Evolves the full density matrix ρ, so noise channels act exactly instead
+//! of through sampled trajectories, and the noisy output distribution comes
+//! out without shot noise. ρ is stored vectorized as a 2n-qubit state vector
+//! (row index in the low n bits, column index in the high n bits), which lets
+//! the state-vector kernels apply U to the row bits and U* to the column bits.
+//! Memory grows as 4ⁿ, hence the 12-qubit limit of the backend.
+
+use crate::backend::{Backend, BackendKind};
+use crate::gates::{self, Matrix2, Matrix4};
+use crate::noise::NoiseModel;
+use crate::{CdfSampler, Circuit, Gate, Operation, QuantumSimulator, C64};
+use nalgebra::DVector;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Exact density-matrix simulator with optional noise
+pub struct DensityMatrixSimulator {
+    n_qubits: usize,
+    /// vec(ρ) on 2n qubits: entry (r, c) at index r + (c << n)
+    rho: QuantumSimulator,
+    noise: NoiseModel,
+    sample_rng: ChaCha8Rng,
+}
+
+impl DensityMatrixSimulator {
+    /// Create a noiseless simulator on `n_qubits` in |0...0⟩⟨0...0|
+    pub fn new(n_qubits: usize, sampling_seed: u64) -> Self {
+        Self::with_noise(n_qubits, NoiseModel::default(), sampling_seed)
+    }
+
+    /// Create a simulator that applies `noise` exactly while running circuits
+    pub fn with_noise(n_qubits: usize, noise: NoiseModel, sampling_seed: u64) -> Self {
+        Self {
+            n_qubits,
+            rho: QuantumSimulator::with_seeds(2 * n_qubits, 0, 0),
+            noise,
+            sample_rng: ChaCha8Rng::seed_from_u64(sampling_seed),
+        }
+    }
+
+    /// Set the number of threads used by gate kernels (see [`QuantumSimulator::set_threads`])
+    pub fn set_threads(&mut self, n_threads: usize) {
+        self.rho.set_threads(n_threads);
+    }
+
+    /// Number of qubits
+    pub fn n_qubits(&self) -> usize {
+        self.n_qubits
+    }
+
+    /// Noise model applied by [`apply_circuit`](Self::apply_circuit)
+    pub fn noise(&self) -> &NoiseModel {
+        &self.noise
+    }
+
+    /// Apply a single-qubit unitary: ρ → UρU†
+    pub fn apply_1q(&mut self, qubit: usize, m: Matrix2) {
+        self.rho.apply_1q(qubit, m);
+        self.rho.apply_1q(qubit + self.n_qubits, m.map(|row| row.map(|x| x.conj())));
+    }
+
+    /// Apply a two-qubit unitary in the |q1 q2⟩ basis: ρ → UρU†
+    pub fn apply_2q(&mut self, q1: usize, q2: usize, m: Matrix4) {
+        let n = self.n_qubits;
+        self.rho.apply_2q(q1, q2, m);
+        self.rho.apply_2q(q1 + n, q2 + n, m.map(|row| row.map(|x| x.conj())));
+    }
+
+    /// Apply a single circuit operation without noise
+    pub fn apply_gate(&mut self, op: &Operation) {
+        let n = self.n_qubits;
+        if let Some(m) = op.gate.matrix_1q() {
+            self.apply_1q(op.qubits[0], m);
+            return;
+        }
+        let (q1, q2) = (op.qubits[0], op.qubits[1]);
+        match op.gate {
+            // Real and diagonal, so U* = U
+            Gate::CZ => {
+                self.rho.cz(q1, q2);
+                self.rho.cz(q1 + n, q2 + n);
+            }
+            Gate::CPhase(phi) => {
+                self.rho.cphase(q1, q2, phi);
+                self.rho.cphase(q1 + n, q2 + n, -phi);
+            }
+            gate => self.apply_2q(q1, q2, gate.matrix_2q().expect("gate is neither single- nor two-qubit")),
+        }
+    }
+
+    /// Apply a single-qubit channel ρ → Σ KρK† given by its Kraus operators
+    pub fn apply_channel_1q(&mut self, qubit: usize, kraus: &[Matrix2]) {
+        // Superoperator Σ K ⊗ K* on the |column row⟩ bits of `qubit`
+        let mut superop = [[C64::new(0.0, 0.0); 4]; 4];
+        for k in kraus {
+            for (i, row) in superop.iter_mut().enumerate() {
+                for (j, entry) in row.iter_mut().enumerate() {
+                    *entry += k[i & 1][j & 1] * k[i >> 1][j >> 1].conj();
+                }
+            }
+        }
+        self.rho.apply_2q(qubit + self.n_qubits, qubit, superop);
+    }
+
+    /// Apply a two-qubit channel ρ → Σ KρK† given by its Kraus operators
+    /// in the |q1 q2⟩ basis
+    pub fn apply_channel_2q(&mut self, q1: usize, q2: usize, kraus: &[Matrix4]) {
+        let original = self.rho.state.clone();
+        let mut sum = DVector::zeros(original.len());
+        for &k in kraus {
+            self.rho.state.copy_from(&original);
+            self.apply_2q(q1, q2, k);
+            sum += &self.rho.state;
+        }
+        self.rho.state = sum;
+    }
+
+    /// Apply the gate errors of the noise model that follow `op`
+    fn apply_gate_noise(&mut self, op: &Operation) {
+        match op.qubits[..] {
+            [q] if self.noise.error_1q(q) > 0.0 => {
+                self.apply_channel_1q(q, &depolarizing_kraus(self.noise.error_1q(q)));
+            }
+            [q1, q2] if self.noise.error_2q(q1, q2) > 0.0 => {
+                // Σ over all 16 Paulis is full depolarization D of both qubits, so
+                // (1-p)ρ + p/15·Σ_{P≠I} PρP = (1 - 16p/15)ρ + 16p/15·D(ρ)
+                let weight = 16.0 * self.noise.error_2q(q1, q2) / 15.0;
+                let original = self.rho.state.clone();
+                self.apply_channel_1q(q1, &depolarizing_kraus(0.75));
+                self.apply_channel_1q(q2, &depolarizing_kraus(0.75));
+                self.rho.state.axpy(C64::new(1.0 - weight, 0.0), &original, C64::new(weight, 0.0));
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply every moment of `circuit` with the noise model's gate errors,
+    /// crosstalk and relaxation
+    pub fn apply_circuit(&mut self, circuit: &Circuit) {
+        assert_eq!(circuit.n_qubits, self.n_qubits, "circuit and simulator qubit counts differ");
+        let crosstalk: Vec<_> = self.noise.crosstalk().collect();
+        for moment in &circuit.moments {
+            for op in &moment.operations {
+                self.apply_gate(op);
+                self.apply_gate_noise(op);
+            }
+            for &(a, b, phi) in &crosstalk {
+                self.apply_gate(&Operation::new(Gate::CPhase(phi), vec![a, b]));
+            }
+            if let Some(relaxation) = self.noise.relaxation.clone() {
+                let duration = relaxation.moment_duration(moment);
+                if duration > 0.0 {
+                    for q in 0..self.n_qubits {
+                        self.apply_channel_1q(q, &relaxation.kraus(q, duration));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Diagonal of ρ: the exact output distribution
+    pub fn probabilities(&self) -> Vec<f64> {
+        let stride = (1 << self.n_qubits) + 1;
+        self.rho.state.iter().step_by(stride).map(|p| p.re.max(0.0)).collect()
+    }
+
+    /// Purity Tr(ρ²), 1 for pure states
+    pub fn purity(&self) -> f64 {
+        // Tr(ρ²) = Σ |ρ_rc|² for Hermitian ρ
+        self.rho.state.norm_squared()
+    }
+
+    /// Sample `n_shots` measurement outcomes from the exact distribution
+    pub fn sample(&mut self, n_shots: usize) -> Vec<usize> {
+        CdfSampler::new(&self.probabilities()).sample(n_shots, &mut self.sample_rng)
+    }
+}
+
+/// Kraus operators of single-qubit depolarizing noise: X, Y or Z with total probability `p`
+fn depolarizing_kraus(p: f64) -> [Matrix2; 4] {
+    let scale = |m: Matrix2, w: f64| m.map(|row| row.map(|x| x * w.sqrt()));
+    let (zero, one) = (C64::new(0.0, 0.0), C64::new(1.0, 0.0));
+    let identity = [[one, zero], [zero, one]];
+    [
+        scale(identity, 1.0 - p),
+        scale(gates::x(), p / 3.0),
+        scale(gates::y(), p / 3.0),
+        scale(gates::z(), p / 3.0),
+    ]
+}
+
+impl Backend for DensityMatrixSimulator {
+    fn name(&self) -> &'static str {
+        BackendKind::DensityMatrix.name()
+    }
+
+    fn n_qubits(&self) -> usize {
+        self.n_qubits
+    }
+
+    fn apply_circuit(&mut self, circuit: &Circuit) {
+        DensityMatrixSimulator::apply_circuit(self, circuit);
+    }
+
+    fn probability(&self, outcome: u128) -> f64 {
+        let i = outcome as usize;
+        self.rho.state[i + (i << self.n_qubits)].re.max(0.0)
+    }
+
+    fn probabilities(&self) -> Option<Vec<f64>> {
+        Some(DensityMatrixSimulator::probabilities(self))
+    }
+
+    fn amplitudes(&self) -> Option<Vec<C64>> {
+        None
+    }
+
+    fn sample(&mut self, n_shots: usize) -> Vec<u128> {
+        DensityMatrixSimulator::sample(self, n_shots)
+            .into_iter()
+            .map(|s| s as u128)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::{sample_trajectories, Relaxation};
+    use crate::{expected_xeb, generate_rcs_circuit, Moment, RcsConfig};
+
+    #[test]
+    fn test_noiseless_matches_state_vector() {
+        let config = RcsConfig { entangler: Gate::SYCAMORE_FSIM, ..RcsConfig::seeded(5, 5, 0, 9) };
+        let circuit = generate_rcs_circuit(&config);
+        let mut sv = QuantumSimulator::with_seed(5, 0);
+        sv.apply_circuit(&circuit);
+        let mut dm = DensityMatrixSimulator::new(5, 0);
+        dm.apply_circuit(&circuit);
+
+        for (p, q) in sv.probabilities().iter().zip(dm.probabilities()) {
+            assert!((p - q).abs() < 1e-12);
+        }
+        assert!((dm.purity() - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_exact_channels() {
+        // H then CNOT with p = 15/16 leaves both qubits maximally mixed
+        let mut circuit = Circuit::new(2);
+        let mut layer = Moment::new();
+        layer.push(Gate::H, vec![0]);
+        circuit.push_moment(layer);
+        let mut layer = Moment::new();
+        layer.push(Gate::CNOT, vec![0, 1]);
+        circuit.push_moment(layer);
+
+        let mut dm = DensityMatrixSimulator::with_noise(2, NoiseModel::depolarizing(0.0, 15.0 / 16.0), 0);
+        dm.apply_circuit(&circuit);
+        assert!(dm.probabilities().iter().all(|p| (p - 0.25).abs() < 1e-12));
+        assert!((dm.purity() - 0.25).abs() < 1e-12);
+
+        // X then one T1 of relaxation leaves e^-1 in |1⟩
+        let mut circuit = Circuit::new(1);
+        let mut layer = Moment::new();
+        layer.push(Gate::X, vec![0]);
+        circuit.push_moment(layer);
+        let mut relaxation = Relaxation::uniform(1, 30.0, 60.0);
+        relaxation.gate_time_1q = 30.0;
+        let mut dm = DensityMatrixSimulator::with_noise(1, NoiseModel::default().with_relaxation(relaxation), 0);
+        dm.apply_circuit(&circuit);
+        assert!((dm.probabilities()[1] - (-1.0f64).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_trajectories_match_density_matrix() {
+        let config = RcsConfig::seeded(4, 4, 0, 12);
+        let circuit = generate_rcs_circuit(&config);
+        let noise = NoiseModel::depolarizing(0.01, 0.05).with_relaxation(Relaxation::uniform(4, 500.0, 600.0));
+
+        let mut ideal = QuantumSimulator::with_seed(4, 0);
+        ideal.apply_circuit(&circuit);
+        let ideal = ideal.probabilities();
+        let mut dm = DensityMatrixSimulator::with_noise(4, noise.clone(), 0);
+        dm.apply_circuit(&circuit);
+        let exact = expected_xeb(&ideal, &dm.probabilities());
+        let sum: f64 = dm.probabilities().iter().sum();
+        assert!((sum - 1.0).abs() < 1e-10);
+
+        let shots = sample_trajectories(&circuit, &noise, 6000, 6000, 3, 1);
+        let mean: f64 = shots.iter().map(|&s| ideal[s as usize]).sum::<f64>() / shots.len() as f64;
+        let sampled = 16.0 * mean - 1.0;
+        assert!((sampled - exact).abs() < 0.05, "{} vs {}", sampled, exact);
+    }
+}
@@ -0,0 +1,300 @@
+//! Standard gate library
+//!
+//! Single-qubit gates are row-major 2x2 unitaries ready for
+//! [`QuantumSimulator::apply_1q`](crate::QuantumSimulator::apply_1q).
+//! Rotations follow the usual convention R_P(θ) = exp(-iθP/2).
+//!
+//! Two-qubit gates are row-major 4x4 unitaries for
+//! [`QuantumSimulator::apply_2q`](crate::QuantumSimulator::apply_2q) in the
+//! basis |q1 q2⟩ = |00⟩, |01⟩, |10⟩, |11⟩, i.e. the first operand is the
+//! more significant bit of the row index.
+
+use crate::C64;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_4, TAU};
+
+/// Row-major 2x2 complex matrix
+pub type Matrix2 = [[C64; 2]; 2];
+
+/// Row-major 4x4 complex matrix
+pub type Matrix4 = [[C64; 4]; 4];
+
+const ZERO: C64 = C64::new(0.0, 0.0);
+const ONE: C64 = C64::new(1.0, 0.0);
+const I: C64 = C64::new(0.0, 1.0);
+
+/// Hadamard
+pub fn h() -> Matrix2 {
+    let h = C64::new(FRAC_1_SQRT_2, 0.0);
+    [[h, h], [h, -h]]
+}
+
+/// Pauli X
+pub fn x() -> Matrix2 {
+    [[ZERO, ONE], [ONE, ZERO]]
+}
+
+/// Pauli Y
+pub fn y() -> Matrix2 {
+    [[ZERO, -I], [I, ZERO]]
+}
+
+/// Pauli Z
+pub fn z() -> Matrix2 {
+    [[ONE, ZERO], [ZERO, -ONE]]
+}
+
+/// Phase gate S = √Z
+pub fn s() -> Matrix2 {
+    [[ONE, ZERO], [ZERO, I]]
+}
+
+/// T gate = √S
+pub fn t() -> Matrix2 {
+    [[ONE, ZERO], [ZERO, C64::from_polar(1.0, FRAC_PI_4)]]
+}
+
+/// Rotation about X by `theta`
+pub fn rx(theta: f64) -> Matrix2 {
+    let c = C64::new((theta / 2.0).cos(), 0.0);
+    let s = C64::new(0.0, -(theta / 2.0).sin());
+    [[c, s], [s, c]]
+}
+
+/// Rotation about Y by `theta`
+pub fn ry(theta: f64) -> Matrix2 {
+    let c = C64::new((theta / 2.0).cos(), 0.0);
+    let s = C64::new((theta / 2.0).sin(), 0.0);
+    [[c, -s], [s, c]]
+}
+
+/// Rotation about Z by `theta`
+pub fn rz(theta: f64) -> Matrix2 {
+    [
+        [C64::from_polar(1.0, -theta / 2.0), ZERO],
+        [ZERO, C64::from_polar(1.0, theta / 2.0)],
+    ]
+}
+
+/// General single-qubit gate U3(θ, φ, λ) in the OpenQASM convention
+pub fn u3(theta: f64, phi: f64, lambda: f64) -> Matrix2 {
+    let c = (theta / 2.0).cos();
+    let s = (theta / 2.0).sin();
+    [
+        [C64::new(c, 0.0), -C64::from_polar(s, lambda)],
+        [C64::from_polar(s, phi), C64::from_polar(c, phi + lambda)],
+    ]
+}
+
+/// Square root of X, phased so that √X·√X = X
+pub fn sqrt_x() -> Matrix2 {
+    let s = C64::new(0.5, 0.5);
+    let t = C64::new(0.5, -0.5);
+    [[s, t], [t, s]]
+}
+
+/// Square root of Y, phased so that √Y·√Y = Y
+pub fn sqrt_y() -> Matrix2 {
+    let s = C64::new(0.5, 0.5);
+    [[s, -s], [s, s]]
+}
+
+/// Square root of W = (X+Y)/√2, phased so that √W·√W = W
+pub fn sqrt_w() -> Matrix2 {
+    let s = C64::new(0.5, 0.5);
+    [
+        [s, C64::new(0.0, -FRAC_1_SQRT_2)],
+        [C64::new(FRAC_1_SQRT_2, 0.0), s],
+    ]
+}
+
+/// Controlled-Z
+pub fn cz() -> Matrix4 {
+    cphase_diag(-ONE)
+}
+
+/// Controlled phase: e^(iφ) on |11⟩, so that cphase(π) = CZ
+pub fn cphase(phi: f64) -> Matrix4 {
+    cphase_diag(C64::from_polar(1.0, phi))
+}
+
+/// Controlled-NOT with the first operand as control
+pub fn cnot() -> Matrix4 {
+    [
+        [ONE, ZERO, ZERO, ZERO],
+        [ZERO, ONE, ZERO, ZERO],
+        [ZERO, ZERO, ZERO, ONE],
+        [ZERO, ZERO, ONE, ZERO],
+    ]
+}
+
+/// SWAP
+pub fn swap() -> Matrix4 {
+    [
+        [ONE, ZERO, ZERO, ZERO],
+        [ZERO, ZERO, ONE, ZERO],
+        [ZERO, ONE, ZERO, ZERO],
+        [ZERO, ZERO, ZERO, ONE],
+    ]
+}
+
+/// iSWAP: swaps |01⟩ and |10⟩ with a phase of i
+pub fn iswap() -> Matrix4 {
+    [
+        [ONE, ZERO, ZERO, ZERO],
+        [ZERO, ZERO, I, ZERO],
+        [ZERO, I, ZERO, ZERO],
+        [ZERO, ZERO, ZERO, ONE],
+    ]
+}
+
+/// Square root of iSWAP
+pub fn sqrt_iswap() -> Matrix4 {
+    let c = C64::new(FRAC_1_SQRT_2, 0.0);
+    let s = C64::new(0.0, FRAC_1_SQRT_2);
+    [
+        [ONE, ZERO, ZERO, ZERO],
+        [ZERO, c, s, ZERO],
+        [ZERO, s, c, ZERO],
+        [ZERO, ZERO, ZERO, ONE],
+    ]
+}
+
+/// Fermionic simulation gate fSim(θ, φ)
+///
+/// Sycamore's native entangler is close to fSim(π/2, π/6).
+pub fn fsim(theta: f64, phi: f64) -> Matrix4 {
+    let c = C64::new(theta.cos(), 0.0);
+    let s = C64::new(0.0, -theta.sin());
+    [
+        [ONE, ZERO, ZERO, ZERO],
+        [ZERO, c, s, ZERO],
+        [ZERO, s, c, ZERO],
+        [ZERO, ZERO, ZERO, C64::from_polar(1.0, -phi)],
+    ]
+}
+
+/// Haar-random two-qubit unitary drawn from `seed`
+///
+/// Gram-Schmidt orthonormalizes the columns of a matrix of standard complex
+/// Gaussians, which yields the Haar measure on U(4) (Mezzadri 2007).
+pub fn haar_unitary_4(seed: u64) -> Matrix4 {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut gaussian = || {
+        // Box-Muller: modulus and phase of a standard complex Gaussian
+        let r = (-(1.0 - rng.gen::<f64>()).ln()).sqrt();
+        C64::from_polar(r, TAU * rng.gen::<f64>())
+    };
+    let mut columns = [[ZERO; 4]; 4];
+    for c in 0..4 {
+        let mut v: [C64; 4] = std::array::from_fn(|_| gaussian());
+        for prev in &columns[..c] {
+            let overlap: C64 = (0..4).map(|k| prev[k].conj() * v[k]).sum();
+            for k in 0..4 {
+                v[k] -= overlap * prev[k];
+            }
+        }
+        let norm = v.iter().map(|z| z.norm_sqr()).sum::<f64>().sqrt();
+        columns[c] = v.map(|z| z / norm);
+    }
+    std::array::from_fn(|r| std::array::from_fn(|c| columns[c][r]))
+}
+
+/// diag(1, 1, 1, phase)
+fn cphase_diag(phase: C64) -> Matrix4 {
+    [
+        [ONE, ZERO, ZERO, ZERO],
+        [ZERO, ONE, ZERO, ZERO],
+        [ZERO, ZERO, ONE, ZERO],
+        [ZERO, ZERO, ZERO, phase],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mul(a: &Matrix2, b: &Matrix2) -> Matrix2 {
+        let mut out = [[ZERO; 2]; 2];
+        for (r, row) in out.iter_mut().enumerate() {
+            for (c, entry) in row.iter_mut().enumerate() {
+                *entry = a[r][0] * b[0][c] + a[r][1] * b[1][c];
+            }
+        }
+        out
+    }
+
+    fn assert_close(a: &Matrix2, b: &Matrix2) {
+        for r in 0..2 {
+            for c in 0..2 {
+                assert!((a[r][c] - b[r][c]).norm() < 1e-12, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gates_are_unitary() {
+        let gates = [
+            h(), x(), y(), z(), s(), t(),
+            rx(0.3), ry(1.1), rz(-2.0), u3(0.4, 1.2, -0.7),
+            sqrt_x(), sqrt_y(), sqrt_w(),
+        ];
+        for g in &gates {
+            let dagger = [
+                [g[0][0].conj(), g[1][0].conj()],
+                [g[0][1].conj(), g[1][1].conj()],
+            ];
+            assert_close(&mul(g, &dagger), &[[ONE, ZERO], [ZERO, ONE]]);
+        }
+    }
+
+    fn mul4(a: &Matrix4, b: &Matrix4) -> Matrix4 {
+        let mut out = [[ZERO; 4]; 4];
+        for (r, row) in out.iter_mut().enumerate() {
+            for (c, entry) in row.iter_mut().enumerate() {
+                *entry = (0..4).map(|k| a[r][k] * b[k][c]).sum();
+            }
+        }
+        out
+    }
+
+    fn assert_close4(a: &Matrix4, b: &Matrix4) {
+        for r in 0..4 {
+            for c in 0..4 {
+                assert!((a[r][c] - b[r][c]).norm() < 1e-12, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_two_qubit_gates() {
+        use std::f64::consts::FRAC_PI_2;
+        let identity = cphase_diag(ONE);
+        assert_close4(&mul4(&swap(), &swap()), &identity);
+        assert_close4(&mul4(&cnot(), &cnot()), &identity);
+        assert_close4(&mul4(&sqrt_iswap(), &sqrt_iswap()), &iswap());
+        // fSim(-π/2, 0) is iSWAP, fSim(0, π) is CZ
+        assert_close4(&fsim(-FRAC_PI_2, 0.0), &iswap());
+        assert_close4(&fsim(0.0, std::f64::consts::PI), &cz());
+        assert_close4(&cphase(std::f64::consts::PI), &cz());
+        assert_close4(&mul4(&cphase(0.4), &cphase(-0.4)), &identity);
+        let u = haar_unitary_4(7);
+        let dagger = std::array::from_fn(|r| std::array::from_fn(|c| u[c][r].conj()));
+        assert_close4(&mul4(&u, &dagger), &identity);
+        assert_ne!(haar_unitary_4(8), u);
+    }
+
+    #[test]
+    fn test_square_roots() {
+        let w = [
+            [ZERO, C64::from_polar(1.0, -FRAC_PI_4)],
+            [C64::from_polar(1.0, FRAC_PI_4), ZERO],
+        ];
+        assert_close(&mul(&sqrt_x(), &sqrt_x()), &x());
+        assert_close(&mul(&sqrt_y(), &sqrt_y()), &y());
+        assert_close(&mul(&sqrt_w(), &sqrt_w()), &w);
+        assert_close(&mul(&t(), &t()), &s());
+        assert_close(&mul(&s(), &s()), &z());
+    }
+}
@@ -8,8 +8,40 @@ use nalgebra::DVector;
 use num_complex::Complex64;
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use rayon::ThreadPool;
 use serde::{Deserialize, Serialize};
-use std::f64::consts::{FRAC_1_SQRT_2, PI};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+pub mod backend;
+pub mod circuit;
+pub mod density;
+pub mod gates;
+mod kernels;
+pub mod mps;
+pub mod noise;
+pub mod porter_thomas;
+pub mod qv;
+pub mod sampling;
+pub mod score;
+pub mod stats;
+pub mod sweep;
+pub mod xeb;
+
+pub use backend::{Backend, BackendKind};
+pub use circuit::{Circuit, Gate, Moment, Operation};
+pub use density::DensityMatrixSimulator;
+pub use gates::{Matrix2, Matrix4};
+pub use mps::MpsSimulator;
+pub use noise::NoiseModel;
+pub use porter_thomas::PorterThomasReport;
+pub use qv::{run_quantum_volume, QvResult};
+pub use sampling::{histogram, CdfSampler};
+pub use score::{score_samples, ScoreResult};
+pub use stats::Summary;
+pub use sweep::{run_depth_sweep, DecayFit, DepthSweep};
+pub use xeb::{Estimate, Estimator};
 
 /// Result of an RCS benchmark run
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,34 +52,264 @@ pub struct RcsResult {
     pub xeb_score: f64,
     pub samples: usize,
     pub runtime_ms: u64,
+    /// Master seed the stream seeds were derived from, `None` for runs seeded
+    /// from entropy; explicit stream seeds take precedence over it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Seed of the circuit stream: gate choices and CZ layout (absent in older results)
+    #[serde(default)]
+    pub circuit_seed: Option<u64>,
+    /// Seed of the measurement sampling stream (absent in older results)
+    #[serde(default)]
+    pub sampling_seed: Option<u64>,
+    /// Two-qubit gate of the entangling layers (absent in older results, which used CZ)
+    #[serde(default)]
+    pub entangler: Option<Gate>,
+    /// Name of the simulation backend (absent in older results, which used the state vector)
+    #[serde(default)]
+    pub backend: Option<String>,
+    /// Bond dimension cap of the MPS backend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bond_dim: Option<usize>,
+    /// Backend's estimate of its fidelity with the exact state (MPS truncation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_fidelity: Option<f64>,
+    /// Truncation fidelity of each moment, whose product is
+    /// `estimated_fidelity` (MPS runs; the first circuit of multi-instance runs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer_fidelities: Option<Vec<f64>>,
+    /// Noise model the samples were drawn under (absent for noiseless runs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise: Option<NoiseModel>,
+    /// Number of noisy trajectories the samples were spread over
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trajectories: Option<usize>,
+    /// Exact XEB expected from the noisy distribution, free of shot noise
+    /// (noisy density-matrix runs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_xeb: Option<f64>,
+    /// Score of the selected estimator after readout-error mitigation, for
+    /// runs with a dense ideal distribution; `xeb_score` is the raw value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mitigated_xeb: Option<f64>,
+    /// SHA-256 of the noise-model file `noise` was loaded from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise_sha256: Option<String>,
+    /// Circuit fidelity F of the white-noise emulated device
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_fidelity: Option<f64>,
+    /// Fidelity the digital error model predicts from the noise model's gate
+    /// and readout error rates, to compare with `xeb_score`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub predicted_fidelity: Option<f64>,
+    /// Standard error of `xeb_score` from the sample variance of its
+    /// per-shot terms (absent in older results, whose scores were clamped to [-0.5, 1])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xeb_std_error: Option<f64>,
+    /// 95% percentile bootstrap confidence interval of `xeb_score`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xeb_bootstrap_ci: Option<[f64; 2]>,
+    /// Estimator behind `xeb_score` (absent in older results, which used linear XEB)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimator: Option<String>,
+    /// Every estimator computed from the samples, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub estimates: BTreeMap<String, Estimate>,
+    /// Individual circuit instances of a multi-instance run; every score
+    /// above is then the mean over instances, with uncertainties across them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub instances: Vec<InstanceScore>,
+    /// Mean, median and spread of the instance scores
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_summary: Option<Summary>,
+    /// Porter-Thomas diagnostics of the (first) ideal circuit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub porter_thomas: Option<PorterThomasReport>,
+    /// Smallest depth at which circuits of this layout anticoncentrate;
+    /// shallower runs do not measure fidelity with linear XEB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anticoncentration_depth: Option<usize>,
+}
+
+/// Seeds and score of one circuit instance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InstanceScore {
+    pub circuit_seed: u64,
+    pub sampling_seed: u64,
+    pub xeb_score: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xeb_std_error: Option<f64>,
 }
 
+/// Parameters of a single RCS benchmark run
+///
+/// The circuit and the measurement samples are driven by two independent
+/// seeds, so the same circuit instance can be re-sampled many times, or the
+/// sampling stream held fixed while the circuit changes.
+#[derive(Debug, Clone)]
+pub struct RcsConfig {
+    pub depth: usize,
+    pub n_qubits: usize,
+    pub n_samples: usize,
+    /// Master seed `circuit_seed` and `sampling_seed` were derived from,
+    /// `None` if they came from entropy
+    pub seed: Option<u64>,
+    /// Seed of the circuit stream (gate choices and CZ layout)
+    pub circuit_seed: u64,
+    /// Seed of the measurement sampling stream
+    pub sampling_seed: u64,
+    /// Two-qubit gate of the entangling layers, CZ by default
+    /// ([`Gate::SYCAMORE_FSIM`] matches the published supremacy circuits)
+    pub entangler: Gate,
+    /// Worker threads for gate kernels (see [`QuantumSimulator::set_threads`])
+    pub threads: usize,
+    /// Simulation backend that executes the circuit
+    pub backend: BackendKind,
+    /// Errors inserted while sampling; the density-matrix backend applies
+    /// circuit noise exactly, every other backend samples state-vector
+    /// trajectories. Readout errors flip the sampled bits. Scoring always
+    /// uses the ideal circuit
+    pub noise: NoiseModel,
+    /// Number of trajectories noisy samples are spread over
+    pub trajectories: usize,
+    /// SHA-256 of the file `noise` was loaded from, recorded in the result
+    pub noise_sha256: Option<String>,
+    /// Number of bootstrap resamples for a confidence interval of the XEB,
+    /// `None` to skip the bootstrap
+    pub bootstrap: Option<usize>,
+    /// Estimator reported as the score; the others are recorded alongside
+    pub estimator: Estimator,
+    /// Number of independent circuit instances to average over
+    pub n_instances: usize,
+    /// Run the instances in parallel on rayon's global pool
+    pub parallel_instances: bool,
+    /// Check the ideal distribution against Porter-Thomas and search the
+    /// depth at which the circuit layout anticoncentrates
+    pub porter_thomas: bool,
+}
+
+impl RcsConfig {
+    /// Configuration with fresh circuit and sampling seeds from OS entropy
+    pub fn new(depth: usize, n_qubits: usize, n_samples: usize) -> Self {
+        Self { seed: None, ..Self::seeded(depth, n_qubits, n_samples, random_seed()) }
+    }
+
+    /// Configuration whose circuit and sampling seeds are both derived from `seed`
+    pub fn seeded(depth: usize, n_qubits: usize, n_samples: usize, seed: u64) -> Self {
+        Self {
+            depth,
+            n_qubits,
+            n_samples,
+            seed: Some(seed),
+            circuit_seed: derive_seed(seed, 0),
+            sampling_seed: derive_seed(seed, 1),
+            entangler: Gate::CZ,
+            threads: 0,
+            backend: BackendKind::StateVector,
+            noise: NoiseModel::default(),
+            trajectories: noise::DEFAULT_TRAJECTORIES,
+            noise_sha256: None,
+            bootstrap: None,
+            estimator: Estimator::Linear,
+            n_instances: 1,
+            parallel_instances: false,
+            porter_thomas: false,
+        }
+    }
+
+    /// Single-instance configuration of circuit instance `index`
+    ///
+    /// Instance 0 keeps the configured seeds, so a one-instance run is
+    /// unchanged; later instances derive both seeds from them.
+    pub fn instance(&self, index: usize) -> RcsConfig {
+        let mut config = RcsConfig { n_instances: 1, ..self.clone() };
+        if index > 0 {
+            let stream = INSTANCE_STREAM + index as u64;
+            config.circuit_seed = derive_seed(self.circuit_seed, stream);
+            config.sampling_seed = derive_seed(self.sampling_seed, stream);
+        }
+        config
+    }
+}
+
+/// Deepest circuit searched for anticoncentration, the CLI's depth limit
+const MAX_ANTICONCENTRATION_DEPTH: usize = 50;
+
+/// Circuits averaged per depth in the anticoncentration search
+const ANTICONCENTRATION_INSTANCES: usize = 5;
+
+/// First seed stream of circuit instances, clear of the streams a single
+/// circuit draws from its seeds
+const INSTANCE_STREAM: u64 = 1 << 32;
+
 /// Complex number shorthand
-type C64 = Complex64;
+pub type C64 = Complex64;
+
+/// Qubit count from which automatic threading switches to parallel kernels
+const PARALLEL_MIN_QUBITS: usize = 14;
+
+/// How gate kernels are scheduled
+#[derive(Clone)]
+enum Threading {
+    /// Parallel on rayon's global pool for large states, serial otherwise
+    Auto,
+    /// Always serial
+    Serial,
+    /// Parallel on a dedicated pool
+    Pool(Arc<ThreadPool>),
+}
+
+impl Threading {
+    /// Run `job`, telling it whether to use the parallel kernels
+    fn run<R: Send>(&self, n_qubits: usize, job: impl FnOnce(bool) -> R + Send) -> R {
+        match self {
+            Threading::Auto => job(n_qubits >= PARALLEL_MIN_QUBITS),
+            Threading::Serial => job(false),
+            Threading::Pool(pool) => pool.install(|| job(true)),
+        }
+    }
+}
+
+/// Dedicated kernel pool of `n_threads` workers, built on first use and
+/// shared by every simulator that asks for that size afterwards
+fn shared_pool(n_threads: usize) -> Option<Arc<ThreadPool>> {
+    static POOLS: Mutex<BTreeMap<usize, Arc<ThreadPool>>> = Mutex::new(BTreeMap::new());
+    let mut pools = POOLS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(pool) = pools.get(&n_threads) {
+        return Some(Arc::clone(pool));
+    }
+    let pool = Arc::new(rayon::ThreadPoolBuilder::new().num_threads(n_threads).build().ok()?);
+    pools.insert(n_threads, Arc::clone(&pool));
+    Some(pool)
+}
 
 /// Quantum state vector simulator
+///
+/// Random gate choices and measurement draws come from separate RNG
+/// streams, so the number of shots never influences the circuit.
 pub struct QuantumSimulator {
     n_qubits: usize,
     state: DVector<C64>,
     rng: ChaCha8Rng,
+    sample_rng: ChaCha8Rng,
+    threading: Threading,
 }
 
 impl QuantumSimulator {
     /// Create new simulator with n qubits in |0...0⟩ state
     pub fn new(n_qubits: usize) -> Self {
-        let dim = 1 << n_qubits;
-        let mut state = DVector::zeros(dim);
-        state[0] = C64::new(1.0, 0.0);
-        
-        Self {
-            n_qubits,
-            state,
-            rng: ChaCha8Rng::from_entropy(),
-        }
+        Self::with_seeds(n_qubits, random_seed(), random_seed())
     }
 
     /// Create with specific seed for reproducibility
+    ///
+    /// Both the circuit and the sampling stream are derived from `seed`.
     pub fn with_seed(n_qubits: usize, seed: u64) -> Self {
+        Self::with_seeds(n_qubits, derive_seed(seed, 0), derive_seed(seed, 1))
+    }
+
+    /// Create with independent seeds for the circuit and sampling streams
+    pub fn with_seeds(n_qubits: usize, circuit_seed: u64, sampling_seed: u64) -> Self {
         let dim = 1 << n_qubits;
         let mut state = DVector::zeros(dim);
         state[0] = C64::new(1.0, 0.0);
@@ -55,100 +317,259 @@ impl QuantumSimulator {
         Self {
             n_qubits,
             state,
-            rng: ChaCha8Rng::seed_from_u64(seed),
+            rng: ChaCha8Rng::seed_from_u64(circuit_seed),
+            sample_rng: ChaCha8Rng::seed_from_u64(sampling_seed),
+            threading: Threading::Auto,
         }
     }
 
+    /// Set the number of threads used by gate kernels
+    ///
+    /// `0` picks automatically (rayon's global pool from 14 qubits up),
+    /// `1` forces the serial path and any other value runs on a dedicated
+    /// pool of that size, which all simulators of that size share. The
+    /// resulting state is identical in every mode.
+    pub fn set_threads(&mut self, n_threads: usize) {
+        self.threading = match n_threads {
+            0 => Threading::Auto,
+            1 => Threading::Serial,
+            n => shared_pool(n).map_or(Threading::Auto, Threading::Pool),
+        };
+    }
+
+    /// Restart the sampling stream from `seed`, leaving the state untouched
+    ///
+    /// Lets the same circuit instance be re-sampled with different seeds.
+    pub fn reseed_sampling(&mut self, seed: u64) {
+        self.sample_rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
     /// Reset to |0...0⟩
     pub fn reset(&mut self) {
         self.state.fill(C64::new(0.0, 0.0));
         self.state[0] = C64::new(1.0, 0.0);
     }
 
+    /// Apply an arbitrary single-qubit unitary `m` (row-major) to `qubit`
+    pub fn apply_1q(&mut self, qubit: usize, m: Matrix2) {
+        let [[a, b], [c, d]] = m;
+        let state = self.state.as_mut_slice();
+        
+        self.threading.run(self.n_qubits, |parallel| {
+            kernels::for_each_pair(state, qubit, parallel, |s0, s1| {
+                let (x, y) = (*s0, *s1);
+                *s0 = a * x + b * y;
+                *s1 = c * x + d * y;
+            });
+        });
+    }
+
+    /// Apply an arbitrary two-qubit unitary `m` (row-major) to `q1`, `q2`
+    ///
+    /// Rows and columns are ordered |q1 q2⟩ = |00⟩, |01⟩, |10⟩, |11⟩.
+    pub fn apply_2q(&mut self, q1: usize, q2: usize, m: Matrix4) {
+        assert_ne!(q1, q2, "two-qubit gate needs distinct qubits");
+        let state = self.state.as_mut_slice();
+        
+        self.threading.run(self.n_qubits, |parallel| {
+            kernels::for_each_quad(state, q1.max(q2), q1.min(q2), parallel, |[a00, a01, a10, a11]| {
+                // Reorder the |hi lo⟩ quad into the matrix basis |q1 q2⟩
+                let quad = if q1 > q2 { [a00, a01, a10, a11] } else { [a00, a10, a01, a11] };
+                let amps = [*quad[0], *quad[1], *quad[2], *quad[3]];
+                for (row, amp) in m.iter().zip(quad) {
+                    *amp = row[0] * amps[0] + row[1] * amps[1] + row[2] * amps[2] + row[3] * amps[3];
+                }
+            });
+        });
+    }
+
     /// Apply Hadamard gate to qubit
     pub fn hadamard(&mut self, qubit: usize) {
-        let h = FRAC_1_SQRT_2;
-        let dim = 1 << self.n_qubits;
-        
-        for i in 0..dim {
-            if (i >> qubit) & 1 == 0 {
-                let j = i | (1 << qubit);
-                let a = self.state[i];
-                let b = self.state[j];
-                self.state[i] = C64::new(h, 0.0) * (a + b);
-                self.state[j] = C64::new(h, 0.0) * (a - b);
-            }
-        }
+        self.apply_1q(qubit, gates::h());
+    }
+
+    /// Apply Pauli X gate to qubit
+    pub fn x(&mut self, qubit: usize) {
+        self.apply_1q(qubit, gates::x());
+    }
+
+    /// Apply Pauli Y gate to qubit
+    pub fn y(&mut self, qubit: usize) {
+        self.apply_1q(qubit, gates::y());
+    }
+
+    /// Apply Pauli Z gate to qubit
+    pub fn z(&mut self, qubit: usize) {
+        self.apply_1q(qubit, gates::z());
+    }
+
+    /// Apply S (phase) gate to qubit
+    pub fn s(&mut self, qubit: usize) {
+        self.apply_1q(qubit, gates::s());
+    }
+
+    /// Apply T gate to qubit
+    pub fn t(&mut self, qubit: usize) {
+        self.apply_1q(qubit, gates::t());
+    }
+
+    /// Rotate qubit about X by `theta`
+    pub fn rx(&mut self, qubit: usize, theta: f64) {
+        self.apply_1q(qubit, gates::rx(theta));
+    }
+
+    /// Rotate qubit about Y by `theta`
+    pub fn ry(&mut self, qubit: usize, theta: f64) {
+        self.apply_1q(qubit, gates::ry(theta));
+    }
+
+    /// Rotate qubit about Z by `theta`
+    pub fn rz(&mut self, qubit: usize, theta: f64) {
+        self.apply_1q(qubit, gates::rz(theta));
+    }
+
+    /// Apply the general U3(θ, φ, λ) gate to qubit
+    pub fn u3(&mut self, qubit: usize, theta: f64, phi: f64, lambda: f64) {
+        self.apply_1q(qubit, gates::u3(theta, phi, lambda));
+    }
+
+    /// Apply sqrt(X) to qubit
+    pub fn sqrt_x(&mut self, qubit: usize) {
+        self.apply_1q(qubit, gates::sqrt_x());
+    }
+
+    /// Apply sqrt(Y) to qubit
+    pub fn sqrt_y(&mut self, qubit: usize) {
+        self.apply_1q(qubit, gates::sqrt_y());
+    }
+
+    /// Apply sqrt(W) to qubit, W = (X+Y)/√2
+    pub fn sqrt_w(&mut self, qubit: usize) {
+        self.apply_1q(qubit, gates::sqrt_w());
     }
 
     /// Apply random single-qubit rotation (sqrt(X), sqrt(Y), or sqrt(W))
     pub fn random_single_gate(&mut self, qubit: usize) {
-        let gate_type = self.rng.gen_range(0..3);
-        let dim = 1 << self.n_qubits;
-        
-        // sqrt(X), sqrt(Y), sqrt(W) gates used in Google's RCS
-        let (a, b, c, d) = match gate_type {
-            0 => { // sqrt(X)
-                let s = C64::new(0.5, 0.5);
-                let t = C64::new(0.5, -0.5);
-                (s, t, t, s)
-            }
-            1 => { // sqrt(Y)
-                let s = C64::new(0.5, 0.5);
-                let t = C64::new(-0.5, -0.5);
-                (s, t, -t, s)
-            }
-            _ => { // sqrt(W) = (sqrt(X) + sqrt(Y)) / sqrt(2)
-                let angle = PI / 4.0;
-                let cos = C64::new(angle.cos(), 0.0);
-                let sin_p = C64::new(0.0, angle.sin());
-                let sin_m = C64::new(0.0, -angle.sin());
-                (cos, sin_m, sin_p, cos)
-            }
-        };
-        
-        for i in 0..dim {
-            if (i >> qubit) & 1 == 0 {
-                let j = i | (1 << qubit);
-                let x = self.state[i];
-                let y = self.state[j];
-                self.state[i] = a * x + b * y;
-                self.state[j] = c * x + d * y;
-            }
+        match random_sqrt_gate(&mut self.rng) {
+            Gate::SqrtX => self.sqrt_x(qubit),
+            Gate::SqrtY => self.sqrt_y(qubit),
+            _ => self.sqrt_w(qubit),
         }
     }
 
     /// Apply CZ (Controlled-Z) gate between two qubits
     pub fn cz(&mut self, q1: usize, q2: usize) {
-        let dim = 1 << self.n_qubits;
-        let mask = (1 << q1) | (1 << q2);
+        assert_ne!(q1, q2, "two-qubit gate needs distinct qubits");
+        let state = self.state.as_mut_slice();
         
-        for i in 0..dim {
-            // Apply -1 phase when both qubits are |1⟩
-            if (i & mask) == mask {
-                self.state[i] = -self.state[i];
+        self.threading.run(self.n_qubits, |parallel| {
+            kernels::for_each_quad(state, q1.max(q2), q1.min(q2), parallel, |[_, _, _, a11]| {
+                // Apply -1 phase when both qubits are |1⟩
+                *a11 = -*a11;
+            });
+        });
+    }
+
+    /// Apply a controlled phase e^(iφ) on |11⟩ between two qubits
+    pub fn cphase(&mut self, q1: usize, q2: usize, phi: f64) {
+        assert_ne!(q1, q2, "two-qubit gate needs distinct qubits");
+        let phase = C64::from_polar(1.0, phi);
+        let state = self.state.as_mut_slice();
+        
+        self.threading.run(self.n_qubits, |parallel| {
+            kernels::for_each_quad(state, q1.max(q2), q1.min(q2), parallel, |[_, _, _, a11]| {
+                *a11 *= phase;
+            });
+        });
+    }
+
+    /// Apply a single circuit operation
+    pub fn apply_operation(&mut self, op: &Operation) {
+        if let Some(m) = op.gate.matrix_1q() {
+            self.apply_1q(op.qubits[0], m);
+            return;
+        }
+        match op.gate {
+            // Diagonal fast paths
+            Gate::CZ => self.cz(op.qubits[0], op.qubits[1]),
+            Gate::CPhase(phi) => self.cphase(op.qubits[0], op.qubits[1], phi),
+            gate => {
+                let m = gate.matrix_2q().expect("gate is neither single- nor two-qubit");
+                self.apply_2q(op.qubits[0], op.qubits[1], m);
             }
         }
     }
 
+    /// Apply every operation of `circuit` in order
+    pub fn apply_circuit(&mut self, circuit: &Circuit) {
+        assert_eq!(circuit.n_qubits, self.n_qubits, "circuit and simulator qubit counts differ");
+        for op in circuit.operations() {
+            self.apply_operation(op);
+        }
+    }
+
+    /// Apply CNOT with `control` and `target`
+    pub fn cnot(&mut self, control: usize, target: usize) {
+        self.apply_2q(control, target, gates::cnot());
+    }
+
+    /// Swap two qubits
+    pub fn swap(&mut self, q1: usize, q2: usize) {
+        self.apply_2q(q1, q2, gates::swap());
+    }
+
+    /// Apply iSWAP between two qubits
+    pub fn iswap(&mut self, q1: usize, q2: usize) {
+        self.apply_2q(q1, q2, gates::iswap());
+    }
+
+    /// Apply sqrt(iSWAP) between two qubits
+    pub fn sqrt_iswap(&mut self, q1: usize, q2: usize) {
+        self.apply_2q(q1, q2, gates::sqrt_iswap());
+    }
+
+    /// Apply fSim(θ, φ) between two qubits
+    pub fn fsim(&mut self, q1: usize, q2: usize, theta: f64, phi: f64) {
+        self.apply_2q(q1, q2, gates::fsim(theta, phi));
+    }
+
     /// Get probability distribution
     pub fn probabilities(&self) -> Vec<f64> {
         self.state.iter().map(|c| c.norm_sqr()).collect()
     }
 
-    /// Sample a measurement outcome
-    pub fn measure(&mut self) -> usize {
-        let probs = self.probabilities();
-        let r: f64 = self.rng.gen();
-        let mut cumsum = 0.0;
-        
-        for (i, p) in probs.iter().enumerate() {
-            cumsum += p;
-            if r < cumsum {
-                return i;
+    /// Reduced density matrix of `qubit`, tracing out all others
+    pub fn reduced_density_matrix(&self, qubit: usize) -> Matrix2 {
+        let stride = 1 << qubit;
+        let mut rho = [[C64::new(0.0, 0.0); 2]; 2];
+        for chunk in self.state.as_slice().chunks(2 * stride) {
+            let (zeros, ones) = chunk.split_at(stride);
+            for (a, b) in zeros.iter().zip(ones) {
+                rho[0][0] += a.norm_sqr();
+                rho[0][1] += a * b.conj();
+                rho[1][1] += b.norm_sqr();
             }
         }
-        probs.len() - 1
+        rho[1][0] = rho[0][1].conj();
+        rho
+    }
+
+    /// Sample a measurement outcome
+    ///
+    /// Rebuilds the distribution on every call; use [`sample`](Self::sample)
+    /// for many shots.
+    pub fn measure(&mut self) -> usize {
+        self.sample(1)[0]
+    }
+
+    /// Sample `n_shots` measurement outcomes from one cumulative distribution
+    pub fn sample(&mut self, n_shots: usize) -> Vec<usize> {
+        CdfSampler::new(&self.probabilities()).sample(n_shots, &mut self.sample_rng)
+    }
+
+    /// Sample `n_shots` outcomes and return how often each basis state occurred
+    pub fn sample_histogram(&mut self, n_shots: usize) -> BTreeMap<usize, usize> {
+        histogram(&self.sample(n_shots))
     }
 
     /// Get number of qubits
@@ -157,6 +578,15 @@ impl QuantumSimulator {
     }
 }
 
+/// Pick one of sqrt(X), sqrt(Y), sqrt(W) uniformly at random
+fn random_sqrt_gate(rng: &mut ChaCha8Rng) -> Gate {
+    match rng.gen_range(0..3) {
+        0 => Gate::SqrtX,
+        1 => Gate::SqrtY,
+        _ => Gate::SqrtW,
+    }
+}
+
 /// Generate random CZ pairs for a layer (nearest-neighbor + some random)
 fn generate_cz_pairs(n_qubits: usize, rng: &mut ChaCha8Rng, layer: usize) -> Vec<(usize, usize)> {
     let mut pairs = Vec::new();
@@ -194,74 +624,425 @@ pub fn run_rcs(depth: usize, n_qubits: usize) -> f64 {
 
 /// Run RCS with custom sample count
 pub fn run_rcs_with_samples(depth: usize, n_qubits: usize, n_samples: usize) -> f64 {
-    let mut sim = QuantumSimulator::new(n_qubits);
-    let mut rng = ChaCha8Rng::from_entropy();
-    let dim = 1 << n_qubits;
+    run_rcs_with_config(&RcsConfig::new(depth, n_qubits, n_samples))
+}
+
+/// Run RCS with every random choice (gates, CZ pairs, samples) driven by `seed`
+///
+/// The same `(depth, n_qubits, n_samples, seed)` always yields the same
+/// circuit, the same samples and therefore the same XEB score.
+pub fn run_rcs_seeded(depth: usize, n_qubits: usize, n_samples: usize, seed: u64) -> f64 {
+    run_rcs_with_config(&RcsConfig::seeded(depth, n_qubits, n_samples, seed))
+}
+
+/// Generate the random circuit for `config` from its circuit seed
+///
+/// Moment 0 applies Hadamards to all qubits; each of the `depth` cycles then
+/// adds a moment of random sqrt(X)/sqrt(Y)/sqrt(W) gates and a moment of
+/// `config.entangler` gates (CZ by default) on the generated pairs.
+pub fn generate_rcs_circuit(config: &RcsConfig) -> Circuit {
+    let n_qubits = config.n_qubits;
+    let mut circuit = Circuit::new(n_qubits);
+    // Gate choices on the first stream of the circuit seed, CZ layout on the second
+    let mut gate_rng = ChaCha8Rng::seed_from_u64(config.circuit_seed);
+    let mut rng = ChaCha8Rng::seed_from_u64(config.circuit_seed);
+    rng.set_stream(1);
     
-    // Build and apply the random circuit
     // Layer 0: Hadamard on all qubits
+    let mut layer = Moment::new();
     for q in 0..n_qubits {
-        sim.hadamard(q);
+        layer.push(Gate::H, vec![q]);
     }
+    circuit.push_moment(layer);
     
     // Subsequent layers: random single-qubit gates + CZ
-    for d in 0..depth {
+    for d in 0..config.depth {
         // Random single-qubit gates
+        let mut layer = Moment::new();
         for q in 0..n_qubits {
-            sim.random_single_gate(q);
+            layer.push(random_sqrt_gate(&mut gate_rng), vec![q]);
         }
+        circuit.push_moment(layer);
         
-        // CZ gates
-        let pairs = generate_cz_pairs(n_qubits, &mut rng, d);
-        for (q1, q2) in pairs {
-            sim.cz(q1, q2);
+        // Entangling gates
+        let mut layer = Moment::new();
+        for (q1, q2) in generate_cz_pairs(n_qubits, &mut rng, d) {
+            layer.push(config.entangler, vec![q1, q2]);
         }
+        circuit.push_moment(layer);
     }
     
-    // Get ideal probability distribution
-    let ideal_probs = sim.probabilities();
-    
-    // Collect samples
-    let mut samples = Vec::with_capacity(n_samples);
-    for _ in 0..n_samples {
-        samples.push(sim.measure());
+    circuit
+}
+
+/// Create the backend selected by `config` in |0...0⟩, with its sampling stream seeded
+///
+/// Only the density-matrix backend carries `config.noise`; the others simulate
+/// the ideal circuit.
+pub fn build_backend(config: &RcsConfig) -> Box<dyn Backend> {
+    match config.backend {
+        BackendKind::StateVector => {
+            let mut sim = QuantumSimulator::with_seeds(config.n_qubits, config.circuit_seed, config.sampling_seed);
+            sim.set_threads(config.threads);
+            Box::new(sim)
+        }
+        BackendKind::Mps { max_bond_dim } => {
+            Box::new(MpsSimulator::new(config.n_qubits, max_bond_dim, config.sampling_seed))
+        }
+        BackendKind::DensityMatrix => {
+            let mut sim = DensityMatrixSimulator::with_noise(config.n_qubits, config.noise.clone(), config.sampling_seed);
+            sim.set_threads(config.threads);
+            Box::new(sim)
+        }
     }
+}
+
+/// Scores and diagnostics of an RCS run
+struct RcsOutcome {
+    xeb_score: f64,
+    estimated_fidelity: Option<f64>,
+    layer_fidelities: Option<Vec<f64>>,
+    expected_xeb: Option<f64>,
+    mitigated_xeb: Option<f64>,
+    target_fidelity: Option<f64>,
+    predicted_fidelity: Option<f64>,
+    xeb_std_error: Option<f64>,
+    xeb_bootstrap_ci: Option<[f64; 2]>,
+    estimates: BTreeMap<String, Estimate>,
+    instances: Vec<InstanceScore>,
+    porter_thomas: Option<PorterThomasReport>,
+}
+
+/// Run RCS as described by `config`
+///
+/// Panics if the selected estimator is undefined for the circuit; see
+/// [`try_run_benchmark_with_config`].
+pub fn run_rcs_with_config(config: &RcsConfig) -> f64 {
+    execute_rcs(config).unwrap_or_else(|e| panic!("{}", e)).xeb_score
+}
+
+/// Run every circuit instance of `config` and average their scores
+///
+/// Each score and uncertainty becomes the mean over instances, with the
+/// standard error and bootstrap taken across the instance scores.
+fn execute_rcs(config: &RcsConfig) -> Result<RcsOutcome, String> {
+    if config.n_instances <= 1 {
+        return execute_instance(config);
+    }
+    let run = |index: usize| {
+        let instance = config.instance(index);
+        execute_instance(&instance).map(|outcome| (instance.circuit_seed, instance.sampling_seed, outcome))
+    };
+    let mut runs: Vec<_> = if config.parallel_instances {
+        (0..config.n_instances).into_par_iter().map(run).collect::<Result<_, _>>()?
+    } else {
+        (0..config.n_instances).map(run).collect::<Result<_, _>>()?
+    };
+    // Diagnostics describe the first circuit only
+    let porter_thomas = runs[0].2.porter_thomas.take();
+    let layer_fidelities = runs[0].2.layer_fidelities.take();
+
+    let scores: Vec<f64> = runs.iter().map(|(_, _, outcome)| outcome.xeb_score).collect();
+    let mean_of = |field: fn(&RcsOutcome) -> Option<f64>| {
+        let values: Vec<f64> = runs.iter().filter_map(|(_, _, outcome)| field(outcome)).collect();
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+    let estimates = runs[0].2.estimates.keys().map(|name| {
+        let values: Vec<f64> =
+            runs.iter().filter_map(|(_, _, outcome)| outcome.estimates.get(name)).map(|e| e.value).collect();
+        let estimate = Estimate {
+            value: values.iter().sum::<f64>() / values.len() as f64,
+            std_error: stats::mean_with_error(&values).map(|(_, error)| error),
+        };
+        (name.clone(), estimate)
+    }).collect();
+    let xeb_bootstrap_ci = config.bootstrap.map(|n_resamples| {
+        let mut rng = ChaCha8Rng::seed_from_u64(config.sampling_seed);
+        rng.set_stream(u64::MAX - 2);
+        stats::bootstrap_mean_ci(&scores, n_resamples, stats::BOOTSTRAP_CONFIDENCE, &mut rng)
+    });
+
+    Ok(RcsOutcome {
+        xeb_score: scores.iter().sum::<f64>() / scores.len() as f64,
+        estimated_fidelity: mean_of(|o| o.estimated_fidelity),
+        layer_fidelities,
+        expected_xeb: mean_of(|o| o.expected_xeb),
+        mitigated_xeb: mean_of(|o| o.mitigated_xeb),
+        target_fidelity: mean_of(|o| o.target_fidelity),
+        predicted_fidelity: mean_of(|o| o.predicted_fidelity),
+        xeb_std_error: stats::mean_with_error(&scores).map(|(_, error)| error),
+        xeb_bootstrap_ci,
+        estimates,
+        instances: runs
+            .iter()
+            .map(|&(circuit_seed, sampling_seed, ref outcome)| InstanceScore {
+                circuit_seed,
+                sampling_seed,
+                xeb_score: outcome.xeb_score,
+                xeb_std_error: outcome.xeb_std_error,
+            })
+            .collect(),
+        porter_thomas,
+    })
+}
+
+/// Generate, simulate, sample and score the single circuit of `config`
+///
+/// Fails if the selected estimator is undefined for the circuit.
+fn execute_instance(config: &RcsConfig) -> Result<RcsOutcome, String> {
+    let circuit = generate_rcs_circuit(config);
+    let noise = &config.noise;
+    let density_matrix = config.backend == BackendKind::DensityMatrix;
+    // Coherent miscalibration belongs to the device, so it is drawn from the circuit seed
+    let device_circuit = noise.device_circuit(&circuit, derive_seed(config.circuit_seed, 2));
+    
+    let mut backend = build_backend(config);
+    backend.apply_circuit(if density_matrix { &device_circuit } else { &circuit });
+    let target_fidelity = noise.white_noise.map(|white_noise| white_noise.fidelity(&circuit));
+    let (samples, clusters) = collect_samples(config, backend.as_mut(), &circuit, &device_circuit);
     
-    // Calculate XEB score
-    // XEB = 2^n * <p_ideal(x)> - 1
-    // where <p_ideal(x)> is the mean ideal probability of sampled bitstrings
-    let mean_prob: f64 = samples.iter()
-        .map(|&s| ideal_probs[s])
-        .sum::<f64>() / n_samples as f64;
+    // A noisy density matrix is scored against a separate noiseless run
+    let ideal = (noise.has_circuit_noise() && density_matrix).then(|| {
+        let mut sim = QuantumSimulator::with_seeds(config.n_qubits, config.circuit_seed, config.sampling_seed);
+        sim.set_threads(config.threads);
+        sim.apply_circuit(&circuit);
+        sim
+    });
+    let ideal_backend: &dyn Backend = match &ideal {
+        Some(sim) => sim,
+        None => backend.as_ref(),
+    };
     
-    let xeb = (dim as f64) * mean_prob - 1.0;
+    // Readout mitigation, the exact expectation and some estimators need the
+    // dense ideal distribution; MPS only builds it when it has to
+    let exact_expectation = density_matrix && !noise.is_noiseless();
+    let needs_dense =
+        noise.readout.is_some() || exact_expectation || config.estimator.needs_distribution() || config.porter_thomas;
+    let ideal_probs = if needs_dense || !matches!(config.backend, BackendKind::Mps { .. }) {
+        ideal_backend.probabilities()
+    } else {
+        None
+    };
     
-    // Clamp to reasonable range
-    xeb.clamp(-0.5, 1.0)
+    // Score with every estimator that can be computed, e.g. linear XEB
+    // 2^n * <p_ideal(x)> - 1 from the mean ideal probability of the samples
+    let sample_probs: Vec<f64> = samples.iter().map(|&s| ideal_backend.probability(s)).collect();
+    let terms: Vec<(Estimator, xeb::ShotTerms)> = Estimator::ALL
+        .into_iter()
+        .filter_map(|e| e.terms(&sample_probs, config.n_qubits, ideal_probs.as_deref()).map(|t| (e, t)))
+        .map(|(e, t)| match &clusters {
+            Some(sizes) => (e, t.clustered(sizes.clone())),
+            None => (e, t),
+        })
+        .collect();
+    let estimates = terms.iter().map(|(e, t)| (e.name().to_string(), t.estimate())).collect();
+    let (_, selected) = terms.iter().find(|(e, _)| *e == config.estimator).ok_or_else(|| {
+        format!(
+            "the {} estimator is undefined for this circuit, whose ideal distribution is flat",
+            config.estimator.name()
+        )
+    })?;
+    let score = selected.estimate();
+    let xeb_bootstrap_ci = config.bootstrap.filter(|_| !samples.is_empty()).map(|n_resamples| {
+        // Third-to-last stream of the sampling seed, below white noise and readout
+        let mut rng = ChaCha8Rng::seed_from_u64(config.sampling_seed);
+        rng.set_stream(u64::MAX - 2);
+        selected.bootstrap_ci(n_resamples, stats::BOOTSTRAP_CONFIDENCE, &mut rng)
+    });
+    let mitigated_xeb = noise.readout.as_ref().zip(ideal_probs.as_ref()).and_then(|(readout, ideal)| {
+        // The estimator's terms for every outcome, weighted by the mitigated distribution
+        let outcome_terms = config.estimator.terms(ideal, config.n_qubits, Some(ideal))?;
+        let shots: Vec<usize> = samples.iter().map(|&s| s as usize).collect();
+        Some(outcome_terms.weighted_value(&readout.mitigate(&histogram(&shots), config.n_qubits)))
+    });
+    let expected = ideal_probs.as_ref().filter(|_| exact_expectation).map(|ideal| {
+        let mut noisy = backend.probabilities().expect("density matrix is dense");
+        // White noise replaces a fraction 1 - F of the shots by uniform outcomes
+        if let Some(fidelity) = target_fidelity {
+            let uniform = (1.0 - fidelity) / noisy.len() as f64;
+            noisy.iter_mut().for_each(|p| *p = fidelity * *p + uniform);
+        }
+        match &noise.readout {
+            Some(readout) => expected_xeb(ideal, &readout.confuse(&noisy)),
+            None => expected_xeb(ideal, &noisy),
+        }
+    });
+    
+    Ok(RcsOutcome {
+        xeb_score: score.value,
+        estimated_fidelity: backend.estimated_fidelity(),
+        layer_fidelities: backend.layer_fidelities(),
+        expected_xeb: expected,
+        mitigated_xeb,
+        target_fidelity,
+        predicted_fidelity: (!noise.is_noiseless()).then(|| noise.predicted_fidelity(&circuit)),
+        xeb_std_error: score.std_error,
+        xeb_bootstrap_ci,
+        estimates,
+        instances: Vec::new(),
+        porter_thomas: ideal_probs.as_deref().filter(|_| config.porter_thomas).map(PorterThomasReport::new),
+    })
+}
+
+/// Draw the `config.n_samples` noisy shots of `circuit` from `backend`,
+/// which has run it (or, on the density matrix, `device_circuit`)
+///
+/// Circuit noise is exact on the density matrix and sampled by trajectories
+/// of `device_circuit` elsewhere; white noise and readout flips come last.
+/// Shots of one trajectory are correlated, so the shots per trajectory are
+/// returned with the samples whenever stochastic noise was sampled that way.
+pub(crate) fn collect_samples(
+    config: &RcsConfig,
+    backend: &mut dyn Backend,
+    circuit: &Circuit,
+    device_circuit: &Circuit,
+) -> (Vec<u128>, Option<Vec<usize>>) {
+    let noise = &config.noise;
+    let n_samples = config.n_samples;
+    let trajectories = noise.white_noise.is_none()
+        && noise.is_stochastic()
+        && config.backend != BackendKind::DensityMatrix;
+    let mut samples = if let Some(white_noise) = noise.white_noise {
+        // Second-to-last stream of the sampling seed, next to the readout flips
+        let mut rng = ChaCha8Rng::seed_from_u64(config.sampling_seed);
+        rng.set_stream(u64::MAX - 1);
+        noise::sample_white_noise(backend, white_noise.fidelity(circuit), n_samples, &mut rng)
+    } else if !noise.has_circuit_noise() || config.backend == BackendKind::DensityMatrix {
+        backend.sample(n_samples)
+    } else {
+        noise::sample_trajectories(
+            device_circuit, noise, n_samples, config.trajectories, config.sampling_seed, config.threads,
+        )
+    };
+    if let Some(readout) = &noise.readout {
+        // Last stream of the sampling seed, trajectories use the first ones
+        let mut rng = ChaCha8Rng::seed_from_u64(config.sampling_seed);
+        rng.set_stream(u64::MAX);
+        readout.apply(&mut samples, config.n_qubits, &mut rng);
+    }
+    let clusters = trajectories.then(|| noise::trajectory_shots(noise, n_samples, config.trajectories));
+    (samples, clusters)
+}
+
+/// XEB expected when sampling from `noisy` and scoring against `ideal`:
+/// 2ⁿ·Σ p_noisy(x)·p_ideal(x) - 1
+pub fn expected_xeb(ideal: &[f64], noisy: &[f64]) -> f64 {
+    assert_eq!(ideal.len(), noisy.len(), "distributions differ in size");
+    let overlap: f64 = ideal.iter().zip(noisy).map(|(p, q)| p * q).sum();
+    ideal.len() as f64 * overlap - 1.0
+}
+
+/// Draw a fresh seed from OS entropy
+fn random_seed() -> u64 {
+    ChaCha8Rng::from_entropy().gen()
+}
+
+/// Derive an independent sub-seed of `seed` for the given stream index
+pub(crate) fn derive_seed(seed: u64, stream: u64) -> u64 {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(stream);
+    rng.gen()
 }
 
 /// Full benchmark run with timing and metadata
 pub fn run_benchmark(depth: usize, n_qubits: usize, n_samples: usize) -> RcsResult {
+    run_benchmark_with_config(&RcsConfig::new(depth, n_qubits, n_samples))
+}
+
+/// Full benchmark run from an explicit seed
+pub fn run_benchmark_seeded(depth: usize, n_qubits: usize, n_samples: usize, seed: u64) -> RcsResult {
+    run_benchmark_with_config(&RcsConfig::seeded(depth, n_qubits, n_samples, seed))
+}
+
+/// Full benchmark run for `config`; both seeds are recorded in the result
+///
+/// Panics if the selected estimator is undefined for the circuit; see
+/// [`try_run_benchmark_with_config`].
+pub fn run_benchmark_with_config(config: &RcsConfig) -> RcsResult {
+    try_run_benchmark_with_config(config).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Full benchmark run for `config`, failing if the selected estimator is
+/// undefined for the circuit: the normalized XEB and the cross-entropy
+/// difference need a dense ideal distribution that is not flat
+pub fn try_run_benchmark_with_config(config: &RcsConfig) -> Result<RcsResult, String> {
+    run_with_anticoncentration_depth(config, search_anticoncentration_depth(config))
+}
+
+/// Anticoncentration depth of `config`'s qubits and entangler if it asks for
+/// the Porter-Thomas diagnostics and fits the state-vector backend
+pub(crate) fn search_anticoncentration_depth(config: &RcsConfig) -> Option<usize> {
+    (config.porter_thomas && config.n_qubits <= BackendKind::StateVector.max_qubits())
+        .then(|| {
+            porter_thomas::anticoncentration_depth(
+                config, MAX_ANTICONCENTRATION_DEPTH, ANTICONCENTRATION_INSTANCES, porter_thomas::ANTICONCENTRATION_TOLERANCE,
+            )
+        })
+        .flatten()
+}
+
+/// Benchmark run for `config` that records an already searched
+/// `anticoncentration_depth`, so sweeps search once for all depths
+pub(crate) fn run_with_anticoncentration_depth(
+    config: &RcsConfig,
+    anticoncentration_depth: Option<usize>,
+) -> Result<RcsResult, String> {
     let start = std::time::Instant::now();
-    let xeb_score = run_rcs_with_samples(depth, n_qubits, n_samples);
+    let outcome = execute_rcs(config)?;
     let runtime_ms = start.elapsed().as_millis() as u64;
     
     let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
     
-    RcsResult {
+    Ok(RcsResult {
         date,
-        depth,
-        qubits: n_qubits,
-        xeb_score,
-        samples: n_samples,
+        depth: config.depth,
+        qubits: config.n_qubits,
+        xeb_score: outcome.xeb_score,
+        samples: config.n_samples,
         runtime_ms,
-    }
+        seed: config.seed,
+        circuit_seed: Some(config.circuit_seed),
+        sampling_seed: Some(config.sampling_seed),
+        entangler: Some(config.entangler),
+        backend: Some(config.backend.name().to_string()),
+        max_bond_dim: match config.backend {
+            BackendKind::Mps { max_bond_dim } => Some(max_bond_dim),
+            BackendKind::StateVector | BackendKind::DensityMatrix => None,
+        },
+        estimated_fidelity: outcome.estimated_fidelity,
+        layer_fidelities: outcome.layer_fidelities,
+        noise: (!config.noise.is_noiseless()).then(|| config.noise.clone()),
+        trajectories: (config.noise.is_stochastic() && config.backend != BackendKind::DensityMatrix)
+            .then_some(config.trajectories),
+        expected_xeb: outcome.expected_xeb,
+        mitigated_xeb: outcome.mitigated_xeb,
+        noise_sha256: config.noise_sha256.clone(),
+        target_fidelity: outcome.target_fidelity,
+        predicted_fidelity: outcome.predicted_fidelity,
+        xeb_std_error: outcome.xeb_std_error,
+        xeb_bootstrap_ci: outcome.xeb_bootstrap_ci,
+        estimator: Some(config.estimator.name().to_string()),
+        estimates: outcome.estimates,
+        instance_summary: (!outcome.instances.is_empty())
+            .then(|| Summary::of(&outcome.instances.iter().map(|i| i.xeb_score).collect::<Vec<_>>())),
+        instances: outcome.instances,
+        porter_thomas: outcome.porter_thomas,
+        anticoncentration_depth,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Build the random circuit for `config` and return the simulator holding its final state
+    fn prepare_rcs_state(config: &RcsConfig) -> QuantumSimulator {
+        let circuit = generate_rcs_circuit(config);
+        let mut sim = QuantumSimulator::with_seeds(config.n_qubits, config.circuit_seed, config.sampling_seed);
+        sim.apply_circuit(&circuit);
+        sim
+    }
+
     #[test]
     fn test_hadamard_superposition() {
         let mut sim = QuantumSimulator::with_seed(1, 42);
@@ -271,6 +1052,32 @@ mod tests {
         assert!((probs[1] - 0.5).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_gate_library_on_state() {
+        // X|0⟩ = |1⟩
+        let mut sim = QuantumSimulator::with_seed(2, 42);
+        sim.x(1);
+        assert!((sim.probabilities()[2] - 1.0).abs() < 1e-12);
+        
+        // H Z H = X
+        let mut sim = QuantumSimulator::with_seed(1, 42);
+        sim.hadamard(0);
+        sim.z(0);
+        sim.hadamard(0);
+        assert!((sim.probabilities()[1] - 1.0).abs() < 1e-12);
+        
+        // RY(θ) gives sin²(θ/2) on |1⟩
+        let mut sim = QuantumSimulator::with_seed(1, 42);
+        sim.ry(0, 1.0);
+        assert!((sim.probabilities()[1] - 0.5f64.sin().powi(2)).abs() < 1e-12);
+        
+        // sqrt(W) keeps the state normalized
+        let mut sim = QuantumSimulator::with_seed(1, 42);
+        sim.sqrt_w(0);
+        let sum: f64 = sim.probabilities().iter().sum();
+        assert!((sum - 1.0).abs() < 1e-12);
+    }
+
     #[test]
     fn test_cz_phase() {
         let mut sim = QuantumSimulator::with_seed(2, 42);
@@ -282,6 +1089,90 @@ mod tests {
         
         // Should have -1 phase on |11⟩
         assert!((sim.state[3].re + 1.0).abs() < 1e-10);
+        
+        // A full turn of conditional phase on top of CZ gives CZ again
+        sim.cphase(1, 0, 2.0 * std::f64::consts::PI);
+        assert!((sim.state[3].re + 1.0).abs() < 1e-10);
+        sim.cphase(0, 1, std::f64::consts::FRAC_PI_2);
+        assert!((sim.state[3] - C64::new(0.0, -1.0)).norm() < 1e-10);
+    }
+
+    #[test]
+    fn test_two_qubit_gates_on_state() {
+        // CNOT after H gives a Bell state
+        let mut sim = QuantumSimulator::with_seed(2, 42);
+        sim.hadamard(0);
+        sim.cnot(0, 1);
+        let probs = sim.probabilities();
+        assert!((probs[0] - 0.5).abs() < 1e-12);
+        assert!((probs[3] - 0.5).abs() < 1e-12);
+        
+        // SWAP moves an excitation, operand order independent
+        let mut sim = QuantumSimulator::with_seed(3, 42);
+        sim.x(0);
+        sim.swap(2, 0);
+        assert!((sim.probabilities()[4] - 1.0).abs() < 1e-12);
+        
+        // Generic kernel with the CZ matrix agrees with the fast path
+        let mut a = QuantumSimulator::with_seed(3, 42);
+        let mut b = QuantumSimulator::with_seed(3, 42);
+        for q in 0..3 {
+            a.hadamard(q);
+            b.hadamard(q);
+        }
+        a.cz(2, 0);
+        b.apply_2q(2, 0, gates::cz());
+        for (x, y) in a.state.iter().zip(b.state.iter()) {
+            assert!((x - y).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_fsim_rcs_circuit() {
+        let config = RcsConfig { entangler: Gate::SYCAMORE_FSIM, ..RcsConfig::seeded(4, 6, 256, 3) };
+        let circuit = generate_rcs_circuit(&config);
+        assert!(circuit.operations().any(|op| op.gate == Gate::SYCAMORE_FSIM));
+        assert!(circuit.operations().all(|op| op.gate != Gate::CZ));
+        
+        let sum: f64 = prepare_rcs_state(&config).probabilities().iter().sum();
+        assert!((sum - 1.0).abs() < 1e-10);
+        assert_eq!(run_benchmark_with_config(&config).entangler, Some(Gate::SYCAMORE_FSIM));
+    }
+
+    #[test]
+    fn test_mps_backend_benchmark() {
+        let config = RcsConfig {
+            backend: BackendKind::Mps { max_bond_dim: 4 },
+            ..RcsConfig::seeded(4, 40, 128, 9)
+        };
+        let result = run_benchmark_with_config(&config);
+        assert_eq!(result.backend.as_deref(), Some("mps"));
+        assert_eq!(result.max_bond_dim, Some(4));
+        let fidelity = result.estimated_fidelity.unwrap();
+        assert!(fidelity > 0.0 && fidelity < 1.0);
+        assert!(result.xeb_score.is_finite());
+        let layers = result.layer_fidelities.unwrap();
+        assert_eq!(layers.len(), generate_rcs_circuit(&config).moments.len());
+        assert!((layers.iter().product::<f64>() - fidelity).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_readout_mitigation_benchmark() {
+        let readout = noise::ReadoutError::uniform(8, 0.03, 0.08);
+        let config = RcsConfig {
+            noise: NoiseModel::default().with_readout(readout),
+            ..RcsConfig::seeded(8, 8, 20000, 13)
+        };
+        let ideal = run_rcs_with_config(&RcsConfig { noise: NoiseModel::default(), ..config.clone() });
+        let result = run_benchmark_with_config(&config);
+        let mitigated = result.mitigated_xeb.unwrap();
+        assert!(result.xeb_score < ideal - 0.2, "raw {} vs ideal {}", result.xeb_score, ideal);
+        assert!((mitigated - ideal).abs() < 0.1, "mitigated {} vs ideal {}", mitigated, ideal);
+        assert!(result.trajectories.is_none());
+
+        // Mitigation follows the selected estimator; normalized XEB recovers 1
+        let normalized = run_benchmark_with_config(&RcsConfig { estimator: Estimator::Normalized, ..config });
+        assert!((normalized.mitigated_xeb.unwrap() - 1.0).abs() < 0.1, "{:?}", normalized.mitigated_xeb);
     }
 
     #[test]
@@ -301,15 +1192,20 @@ mod tests {
 
     #[test]
     fn test_xeb_score_reasonable() {
-        // Run multiple times to account for randomness
+        // The unclamped score scatters around its exact expectation 2ⁿ·Σp² - 1
+        // by about one standard error
         let mut valid_count = 0;
         for _ in 0..10 {
-            let xeb = run_rcs_with_samples(3, 4, 512);
-            if xeb > -0.5 && xeb <= 1.0 {
+            let config = RcsConfig::new(3, 4, 512);
+            let probs = prepare_rcs_state(&config).probabilities();
+            let expected = expected_xeb(&probs, &probs);
+            let result = run_benchmark_with_config(&config);
+            // Circuits with a flat support score exactly, with zero error
+            if (result.xeb_score - expected).abs() <= 3.0 * result.xeb_std_error.unwrap() + 1e-9 {
                 valid_count += 1;
             }
         }
-        // At least 7 out of 10 should be in reasonable range
+        // At least 7 out of 10 should be within three standard errors
         assert!(valid_count >= 7, "Too many XEB scores out of range: {}/10 valid", valid_count);
     }
 
@@ -322,10 +1218,10 @@ mod tests {
             scores.push(run_rcs_with_samples(5, 6, 1024));
         }
         
-        // All scores should be finite and in valid range
+        // All scores should be finite and within the estimator's bounds [-1, 2ⁿ - 1]
         for xeb in &scores {
             assert!(xeb.is_finite(), "XEB is not finite: {}", xeb);
-            assert!(*xeb >= -1.0 && *xeb <= 1.0, "XEB out of range: {}", xeb);
+            assert!(*xeb >= -1.0 && *xeb <= 63.0, "XEB out of range: {}", xeb);
         }
     }
 	
@@ -336,5 +1232,192 @@ mod tests {
         assert_eq!(result.qubits, 4);
         assert_eq!(result.samples, 256);
         assert!(result.runtime_ms < 10000);
+        assert!(result.circuit_seed.is_some());
+        assert!(result.sampling_seed.is_some());
+        assert_eq!(result.backend.as_deref(), Some("statevector"));
+    }
+
+    #[test]
+    fn test_xeb_uncertainty_recorded() {
+        let config = RcsConfig { bootstrap: Some(200), ..RcsConfig::seeded(10, 8, 2000, 77) };
+        let result = run_benchmark_with_config(&config);
+        let error = result.xeb_std_error.unwrap();
+        let [lo, hi] = result.xeb_bootstrap_ci.unwrap();
+        assert!(lo < result.xeb_score && result.xeb_score < hi);
+        assert!(error > 0.0 && hi - lo < 6.0 * error);
+        assert_eq!(run_benchmark_with_config(&config).xeb_bootstrap_ci, Some([lo, hi]));
+        assert!(run_benchmark_seeded(10, 8, 2000, 77).xeb_bootstrap_ci.is_none());
+    }
+
+    #[test]
+    fn test_expected_xeb_includes_white_noise() {
+        // The exact expectation of a white-noise run is F times the ideal one
+        let noise = NoiseModel { white_noise: Some(noise::WhiteNoise::Fidelity(0.5)), ..NoiseModel::default() };
+        let config = RcsConfig { noise, backend: BackendKind::DensityMatrix, ..RcsConfig::seeded(8, 4, 4000, 1) };
+        let probs = prepare_rcs_state(&config).probabilities();
+        let result = run_benchmark_with_config(&config);
+        let expected = result.expected_xeb.unwrap();
+        assert!((expected - 0.5 * expected_xeb(&probs, &probs)).abs() < 1e-9);
+        assert!((result.xeb_score - expected).abs() < 4.0 * result.xeb_std_error.unwrap());
+    }
+
+    #[test]
+    fn test_undefined_estimator_is_an_error() {
+        // One cycle on two qubits leaves a flat ideal distribution
+        let config = RcsConfig { estimator: Estimator::Normalized, ..RcsConfig::seeded(1, 2, 64, 10) };
+        let probs = prepare_rcs_state(&config).probabilities();
+        assert!(probs.iter().all(|&p| p < 1e-12 || (p - probs.iter().cloned().fold(0.0, f64::max)).abs() < 1e-12));
+        let error = try_run_benchmark_with_config(&config).unwrap_err();
+        assert!(error.contains("normalized"), "{}", error);
+        let linear = RcsConfig { estimator: Estimator::Linear, ..config };
+        assert!(try_run_benchmark_with_config(&linear).is_ok());
+    }
+
+    #[test]
+    fn test_instances_aggregate() {
+        let config = RcsConfig { n_instances: 3, ..RcsConfig::seeded(8, 6, 500, 21) };
+        let result = run_benchmark_with_config(&config);
+        let scores: Vec<f64> = result.instances.iter().map(|i| i.xeb_score).collect();
+        assert_eq!(scores.len(), 3);
+        // Instance 0 is the single-instance run, the others get fresh circuits
+        assert_eq!(scores[0], run_benchmark_seeded(8, 6, 500, 21).xeb_score);
+        assert_ne!(result.instances[1].circuit_seed, result.instances[2].circuit_seed);
+        let summary = result.instance_summary.unwrap();
+        assert!((summary.mean - result.xeb_score).abs() < 1e-12);
+        assert!(result.xeb_std_error.unwrap() > 0.0);
+        let parallel = RcsConfig { parallel_instances: true, ..config };
+        assert_eq!(run_benchmark_with_config(&parallel).instances, result.instances);
+    }
+
+    #[test]
+    fn test_estimators_agree_under_white_noise() {
+        let noise = NoiseModel { white_noise: Some(noise::WhiteNoise::Fidelity(0.5)), ..NoiseModel::default() };
+        let config = RcsConfig { noise, estimator: Estimator::Log, ..RcsConfig::seeded(12, 8, 10000, 5) };
+        let result = run_benchmark_with_config(&config);
+        assert_eq!(result.estimator.as_deref(), Some("log"));
+        assert_eq!(result.estimates.len(), Estimator::ALL.len());
+        assert_eq!(result.xeb_score, result.estimates["log"].value);
+        for (name, estimate) in &result.estimates {
+            let error = estimate.std_error.unwrap();
+            assert!((estimate.value - 0.5).abs() < 4.0 * error + 0.02, "{}: {:?}", name, estimate);
+        }
+    }
+
+    #[test]
+    fn test_seeded_run_reproducible() {
+        let a = run_rcs_seeded(6, 6, 512, 1234);
+        let b = run_rcs_seeded(6, 6, 512, 1234);
+        assert_eq!(a.to_bits(), b.to_bits());
+        
+        let result = run_benchmark_seeded(6, 6, 512, 1234);
+        assert_eq!(result.xeb_score.to_bits(), a.to_bits());
+        
+        // The recorded seeds alone reproduce the run
+        let config = RcsConfig {
+            circuit_seed: result.circuit_seed.unwrap(),
+            sampling_seed: result.sampling_seed.unwrap(),
+            ..RcsConfig::new(6, 6, 512)
+        };
+        assert_eq!(run_rcs_with_config(&config).to_bits(), a.to_bits());
+
+        // So does the master seed
+        assert_eq!(result.seed, Some(1234));
+        assert_eq!(run_benchmark_seeded(6, 6, 512, result.seed.unwrap()).xeb_score.to_bits(), a.to_bits());
+        assert_eq!(run_benchmark(6, 6, 512).seed, None);
+    }
+
+    #[test]
+    fn test_sampling_seed_does_not_change_circuit() {
+        let base = RcsConfig::seeded(5, 5, 256, 7);
+        let resampled = RcsConfig { sampling_seed: 99, n_samples: 2048, ..base.clone() };
+        
+        let p1 = prepare_rcs_state(&base).probabilities();
+        let p2 = prepare_rcs_state(&resampled).probabilities();
+        assert_eq!(p1, p2);
+        
+        let recircuited = RcsConfig { circuit_seed: 99, ..base.clone() };
+        assert_ne!(p1, prepare_rcs_state(&recircuited).probabilities());
+    }
+
+    #[test]
+    fn test_circuit_matches_direct_gate_calls() {
+        let config = RcsConfig::seeded(4, 5, 128, 11);
+        
+        // Same gate sequence through the imperative API
+        let mut direct = QuantumSimulator::with_seeds(5, config.circuit_seed, 0);
+        let mut rng = ChaCha8Rng::seed_from_u64(config.circuit_seed);
+        rng.set_stream(1);
+        for q in 0..5 {
+            direct.hadamard(q);
+        }
+        for d in 0..4 {
+            for q in 0..5 {
+                direct.random_single_gate(q);
+            }
+            for (q1, q2) in generate_cz_pairs(5, &mut rng, d) {
+                direct.cz(q1, q2);
+            }
+        }
+        
+        let circuit = generate_rcs_circuit(&config);
+        assert_eq!(circuit.moments.len(), 2 * 4 + 1);
+        let mut replayed = QuantumSimulator::with_seed(5, 0);
+        replayed.apply_circuit(&circuit);
+        assert_eq!(direct.probabilities(), replayed.probabilities());
+    }
+
+    #[test]
+    fn test_batched_sampling_matches_single_shots() {
+        let config = RcsConfig::seeded(4, 5, 0, 21);
+        let mut batched = prepare_rcs_state(&config);
+        let mut single = prepare_rcs_state(&config);
+        
+        let shots = batched.sample(200);
+        let one_by_one: Vec<usize> = (0..200).map(|_| single.measure()).collect();
+        assert_eq!(shots, one_by_one);
+        
+        single.reseed_sampling(config.sampling_seed);
+        let counts = single.sample_histogram(200);
+        assert_eq!(counts.values().sum::<usize>(), 200);
+        assert_eq!(counts, histogram(&shots));
+    }
+
+    #[test]
+    fn test_parallel_kernels_match_serial() {
+        let config = RcsConfig { entangler: Gate::SYCAMORE_FSIM, ..RcsConfig::seeded(3, 15, 0, 5) };
+        let mut circuit = generate_rcs_circuit(&config);
+        let mut extra = Moment::new();
+        extra.push(Gate::CZ, vec![14, 0]);
+        extra.push(Gate::CNOT, vec![3, 13]);
+        circuit.push_moment(extra);
+        
+        let mut serial = QuantumSimulator::with_seed(15, 0);
+        serial.set_threads(1);
+        serial.apply_circuit(&circuit);
+        let mut parallel = QuantumSimulator::with_seed(15, 0);
+        parallel.set_threads(4);
+        parallel.apply_circuit(&circuit);
+        
+        assert_eq!(serial.state, parallel.state);
+
+        // Simulators with the same thread count run on one pool
+        let mut other = QuantumSimulator::with_seed(15, 1);
+        other.set_threads(4);
+        match (&parallel.threading, &other.threading) {
+            (Threading::Pool(a), Threading::Pool(b)) => assert!(Arc::ptr_eq(a, b)),
+            _ => panic!("expected dedicated pools"),
+        }
+    }
+
+    #[test]
+    fn test_reseed_sampling_repeats_draws() {
+        let mut sim = QuantumSimulator::with_seeds(3, 1, 2);
+        for q in 0..3 {
+            sim.hadamard(q);
+        }
+        let first: Vec<usize> = (0..32).map(|_| sim.measure()).collect();
+        sim.reseed_sampling(2);
+        let second: Vec<usize> = (0..32).map(|_| sim.measure()).collect();
+        assert_eq!(first, second);
     }
 }
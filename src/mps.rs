@@ -0,0 +1,479 @@
+//! Matrix Product State simulator
+//!
+//! Stores the state as a chain of site tensors, one per qubit in the 1D
+//! layout of the RCS generator. Two-qubit gates contract neighbouring sites,
+//! apply the 4x4 unitary and split them again by SVD, keeping at most
+//! `max_bond_dim` singular values. The discarded weight of every split is
+//! tracked, and their product estimates the fidelity of the simulated state
+//! with the exact one. Non-adjacent gates are routed through SWAP chains.
+//!
+//! Memory and time scale with the bond dimension χ instead of 2ⁿ, so
+//! low-depth circuits on 40–100 qubits are tractable.
+
+use crate::backend::{Backend, BackendKind};
+use crate::gates::{self, Matrix2, Matrix4};
+use crate::{Circuit, Operation, C64};
+use nalgebra::{DMatrix, DVector};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+/// Default cap on the bond dimension
+pub const DEFAULT_MAX_BOND_DIM: usize = 64;
+
+/// Largest qubit count for which the dense distribution is materialized
+const MAX_DENSE_QUBITS: usize = 20;
+
+/// Relative singular-value weight below which a value counts as zero
+const ZERO_WEIGHT: f64 = 1e-15;
+
+/// Relative off-diagonal size at which a Jacobi rotation is skipped
+const JACOBI_TOLERANCE: f64 = 1e-14;
+
+/// Upper bound on Jacobi sweeps; convergence is quadratic, so this is a safety net
+const JACOBI_MAX_SWEEPS: usize = 60;
+
+/// Matrix Product State simulator with bond-dimension truncation
+pub struct MpsSimulator {
+    n_qubits: usize,
+    max_bond_dim: usize,
+    /// `tensors[q][s]` is the χ_left × χ_right matrix of qubit q in state |s⟩
+    tensors: Vec<[DMatrix<C64>; 2]>,
+    /// Site holding the orthogonality center; sites to its left are
+    /// left-orthonormal, sites to its right right-orthonormal
+    center: usize,
+    /// Product of all truncation fidelities so far
+    fidelity: f64,
+    /// Truncation fidelity of each applied moment
+    layer_fidelities: Vec<f64>,
+    sample_rng: ChaCha8Rng,
+}
+
+impl MpsSimulator {
+    /// Create an MPS on `n_qubits` in |0...0⟩
+    pub fn new(n_qubits: usize, max_bond_dim: usize, sampling_seed: u64) -> Self {
+        assert!(n_qubits <= 128, "outcomes are u128 bitstrings");
+        assert!(max_bond_dim >= 1, "bond dimension must be at least 1");
+        let zero = || [DMatrix::from_element(1, 1, C64::new(1.0, 0.0)), DMatrix::zeros(1, 1)];
+        Self {
+            n_qubits,
+            max_bond_dim,
+            tensors: (0..n_qubits).map(|_| zero()).collect(),
+            center: 0,
+            fidelity: 1.0,
+            layer_fidelities: Vec::new(),
+            sample_rng: ChaCha8Rng::seed_from_u64(sampling_seed),
+        }
+    }
+
+    /// Number of qubits
+    pub fn n_qubits(&self) -> usize {
+        self.n_qubits
+    }
+
+    /// Bond dimension cap
+    pub fn max_bond_dim(&self) -> usize {
+        self.max_bond_dim
+    }
+
+    /// Current bond dimensions between neighbouring qubits
+    pub fn bond_dims(&self) -> Vec<usize> {
+        self.tensors[..self.n_qubits.saturating_sub(1)]
+            .iter()
+            .map(|t| t[0].ncols())
+            .collect()
+    }
+
+    /// Estimated fidelity with the exact state: product of all truncation fidelities
+    pub fn fidelity(&self) -> f64 {
+        self.fidelity
+    }
+
+    /// Truncation fidelity of each moment applied through [`apply_circuit`](Self::apply_circuit)
+    pub fn layer_fidelities(&self) -> &[f64] {
+        &self.layer_fidelities
+    }
+
+    /// Apply a single-qubit unitary
+    pub fn apply_1q(&mut self, qubit: usize, m: Matrix2) {
+        let [a0, a1] = &self.tensors[qubit];
+        let new = [
+            a0 * m[0][0] + a1 * m[0][1],
+            a0 * m[1][0] + a1 * m[1][1],
+        ];
+        self.tensors[qubit] = new;
+    }
+
+    /// Apply a two-qubit unitary in the |q1 q2⟩ basis, returning its truncation fidelity
+    pub fn apply_2q(&mut self, q1: usize, q2: usize, m: Matrix4) -> f64 {
+        assert_ne!(q1, q2, "two-qubit gate needs distinct qubits");
+        let (lo, hi) = (q1.min(q2), q1.max(q2));
+        // Matrix basis with the lower site as the more significant bit
+        let m = if q1 < q2 { m } else { swap_operands(&m) };
+
+        let mut fidelity = 1.0;
+        // Bring qubit `hi` next to `lo`, apply, and move it back
+        for site in (lo + 1..hi).rev() {
+            fidelity *= self.apply_adjacent(site, &gates::swap());
+        }
+        fidelity *= self.apply_adjacent(lo, &m);
+        for site in lo + 1..hi {
+            fidelity *= self.apply_adjacent(site, &gates::swap());
+        }
+        fidelity
+    }
+
+    /// Apply a single circuit operation, returning its truncation fidelity
+    pub fn apply_operation(&mut self, op: &Operation) -> f64 {
+        if let Some(m) = op.gate.matrix_1q() {
+            self.apply_1q(op.qubits[0], m);
+            return 1.0;
+        }
+        let m = op.gate.matrix_2q().expect("gate is neither single- nor two-qubit");
+        self.apply_2q(op.qubits[0], op.qubits[1], m)
+    }
+
+    /// Apply every moment of `circuit`, recording each moment's truncation fidelity
+    pub fn apply_circuit(&mut self, circuit: &Circuit) {
+        assert_eq!(circuit.n_qubits, self.n_qubits, "circuit and simulator qubit counts differ");
+        for moment in &circuit.moments {
+            let layer: f64 = moment.operations.iter().map(|op| self.apply_operation(op)).product();
+            self.fidelity *= layer;
+            self.layer_fidelities.push(layer);
+        }
+    }
+
+    /// Amplitude of the basis state `outcome` (qubit q in bit q)
+    pub fn amplitude(&self, outcome: u128) -> C64 {
+        let mut env = DMatrix::from_element(1, 1, C64::new(1.0, 0.0));
+        for (q, site) in self.tensors.iter().enumerate() {
+            env *= &site[((outcome >> q) & 1) as usize];
+        }
+        env[(0, 0)]
+    }
+
+    /// All 2ⁿ amplitudes from one left-to-right sweep
+    ///
+    /// After q sites, row r of the environment holds the partial contraction
+    /// for the first q bits of r; stacking its products with both states of
+    /// the next site adds bit q. The environment never exceeds 2ⁿ entries,
+    /// since the bond dimension after q sites is at most 2ⁿ⁻q.
+    fn dense_amplitudes(&self) -> Vec<C64> {
+        let mut env = DMatrix::from_element(1, 1, C64::new(1.0, 0.0));
+        for [a0, a1] in &self.tensors {
+            let rows = env.nrows();
+            let mut next = DMatrix::zeros(2 * rows, a0.ncols());
+            next.rows_mut(0, rows).copy_from(&(&env * a0));
+            next.rows_mut(rows, rows).copy_from(&(&env * a1));
+            env = next;
+        }
+        env.column(0).iter().copied().collect()
+    }
+
+    /// Draw `n_shots` outcomes by sequential conditional sampling
+    ///
+    /// With the orthogonality center on the first qubit, the marginal of
+    /// each qubit given the ones already fixed follows from a left
+    /// environment vector alone, so a shot costs O(n χ²).
+    pub fn sample(&mut self, n_shots: usize) -> Vec<u128> {
+        self.move_center(0);
+        (0..n_shots)
+            .map(|_| {
+                let mut outcome = 0u128;
+                let mut env = DMatrix::from_element(1, 1, C64::new(1.0, 0.0));
+                for (q, site) in self.tensors.iter().enumerate() {
+                    let v0 = &env * &site[0];
+                    let v1 = &env * &site[1];
+                    let (w0, w1) = (v0.norm_squared(), v1.norm_squared());
+                    if self.sample_rng.gen::<f64>() * (w0 + w1) < w0 {
+                        env = v0.unscale(w0.sqrt());
+                    } else {
+                        outcome |= 1 << q;
+                        env = v1.unscale(w1.sqrt());
+                    }
+                }
+                outcome
+            })
+            .collect()
+    }
+
+    /// Contract sites `site`, `site + 1` with `m`, split by SVD and truncate
+    fn apply_adjacent(&mut self, site: usize, m: &Matrix4) -> f64 {
+        self.move_center(site);
+        let chi_l = self.tensors[site][0].nrows();
+        let chi_r = self.tensors[site + 1][0].ncols();
+
+        let theta: Vec<Vec<DMatrix<C64>>> = (0..2)
+            .map(|t1| (0..2).map(|t2| &self.tensors[site][t1] * &self.tensors[site + 1][t2]).collect())
+            .collect();
+
+        // Rows (s1, left bond), columns (s2, right bond)
+        let mut joint = DMatrix::zeros(2 * chi_l, 2 * chi_r);
+        for s1 in 0..2 {
+            for s2 in 0..2 {
+                let mut block = DMatrix::zeros(chi_l, chi_r);
+                for t1 in 0..2 {
+                    for t2 in 0..2 {
+                        block += &theta[t1][t2] * m[2 * s1 + s2][2 * t1 + t2];
+                    }
+                }
+                joint.view_mut((s1 * chi_l, s2 * chi_r), (chi_l, chi_r)).copy_from(&block);
+            }
+        }
+
+        let (u, sigma, v_t, fidelity) = truncated_svd(joint, self.max_bond_dim);
+        let sv_t = diagonal(&sigma) * v_t;
+        for s in 0..2 {
+            self.tensors[site][s] = u.rows(s * chi_l, chi_l).into_owned();
+            self.tensors[site + 1][s] = sv_t.columns(s * chi_r, chi_r).into_owned();
+        }
+        self.center = site + 1;
+        fidelity
+    }
+
+    /// Move the orthogonality center to `target` without truncation
+    fn move_center(&mut self, target: usize) {
+        while self.center < target {
+            let c = self.center;
+            let (chi_l, chi_r) = self.tensors[c][0].shape();
+            let mut stacked = DMatrix::zeros(2 * chi_l, chi_r);
+            for s in 0..2 {
+                stacked.view_mut((s * chi_l, 0), (chi_l, chi_r)).copy_from(&self.tensors[c][s]);
+            }
+            let (u, sigma, v_t, _) = truncated_svd(stacked, usize::MAX);
+            let carry = diagonal(&sigma) * v_t;
+            for s in 0..2 {
+                self.tensors[c][s] = u.rows(s * chi_l, chi_l).into_owned();
+                self.tensors[c + 1][s] = &carry * &self.tensors[c + 1][s];
+            }
+            self.center += 1;
+        }
+        while self.center > target {
+            let c = self.center;
+            let (chi_l, chi_r) = self.tensors[c][0].shape();
+            let mut stacked = DMatrix::zeros(chi_l, 2 * chi_r);
+            for s in 0..2 {
+                stacked.view_mut((0, s * chi_r), (chi_l, chi_r)).copy_from(&self.tensors[c][s]);
+            }
+            let (u, sigma, v_t, _) = truncated_svd(stacked, usize::MAX);
+            let carry = u * diagonal(&sigma);
+            for s in 0..2 {
+                self.tensors[c][s] = v_t.columns(s * chi_r, chi_r).into_owned();
+                self.tensors[c - 1][s] = &self.tensors[c - 1][s] * &carry;
+            }
+            self.center -= 1;
+        }
+    }
+}
+
+/// SVD keeping at most `max_rank` singular values (largest first), renormalized
+/// to the original norm; also returns the kept fraction of the squared norm
+fn truncated_svd(m: DMatrix<C64>, max_rank: usize) -> (DMatrix<C64>, Vec<f64>, DMatrix<C64>, f64) {
+    let (u, singular_values, v_h) = jacobi_svd(&m);
+    let mut order: Vec<usize> = (0..singular_values.len()).collect();
+    order.sort_by(|&a, &b| singular_values[b].total_cmp(&singular_values[a]));
+
+    let total: f64 = singular_values.iter().map(|s| s * s).sum();
+    let keep: Vec<usize> = order
+        .into_iter()
+        .take(max_rank)
+        .enumerate()
+        .take_while(|&(rank, i)| rank == 0 || singular_values[i].powi(2) > ZERO_WEIGHT * total)
+        .map(|(_, i)| i)
+        .collect();
+    let kept: f64 = keep.iter().map(|&i| singular_values[i].powi(2)).sum();
+    let scale = (total / kept).sqrt();
+
+    let u = u.select_columns(&keep);
+    let v_h = v_h.select_rows(&keep);
+    let sigma = keep.iter().map(|&i| singular_values[i] * scale).collect();
+    (u, sigma, v_h, kept / total)
+}
+
+/// Thin SVD `m = U diag(σ) Vᴴ` by one-sided Jacobi rotations
+///
+/// Used instead of nalgebra's complex SVD, which loses accuracy on the
+/// nearly rank-deficient matrices that MPS splits produce. Columns of `U`
+/// belonging to zero singular values are left zero.
+fn jacobi_svd(m: &DMatrix<C64>) -> (DMatrix<C64>, Vec<f64>, DMatrix<C64>) {
+    // Rotate the smaller dimension: m = (mᴴ)ᴴ = V diag(σ) Uᴴ
+    if m.ncols() > m.nrows() {
+        let (u, sigma, v_h) = jacobi_svd(&m.adjoint());
+        return (v_h.adjoint(), sigma, u.adjoint());
+    }
+
+    let n = m.ncols();
+    let mut w = m.clone();
+    let mut v = DMatrix::<C64>::identity(n, n);
+    for _ in 0..JACOBI_MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let alpha = w.column(p).norm_squared();
+                let beta = w.column(q).norm_squared();
+                let gamma = w.column(p).dotc(&w.column(q));
+                if gamma.norm() <= JACOBI_TOLERANCE * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+
+                // Phase-align column q, then apply the real Jacobi rotation
+                // that makes columns p and q orthogonal
+                let phase = (gamma / gamma.norm()).conj();
+                let zeta = (beta - alpha) / (2.0 * gamma.norm());
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = c * t;
+                for mat in [&mut w, &mut v] {
+                    for k in 0..mat.nrows() {
+                        let xp = mat[(k, p)];
+                        let xq = mat[(k, q)] * phase;
+                        mat[(k, p)] = xp * c - xq * s;
+                        mat[(k, q)] = xp * s + xq * c;
+                    }
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let sigma: Vec<f64> = w.column_iter().map(|col| col.norm()).collect();
+    for (j, &s) in sigma.iter().enumerate() {
+        let mut col = w.column_mut(j);
+        if s > 0.0 {
+            col.unscale_mut(s);
+        } else {
+            col.fill(C64::new(0.0, 0.0));
+        }
+    }
+    (w, sigma, v.adjoint())
+}
+
+/// Square diagonal matrix of singular values
+fn diagonal(sigma: &[f64]) -> DMatrix<C64> {
+    DMatrix::from_diagonal(&DVector::from_iterator(sigma.len(), sigma.iter().map(|&s| C64::new(s, 0.0))))
+}
+
+/// Express a |q1 q2⟩ matrix in the |q2 q1⟩ basis
+fn swap_operands(m: &Matrix4) -> Matrix4 {
+    const PERM: [usize; 4] = [0, 2, 1, 3];
+    let mut out = *m;
+    for r in 0..4 {
+        for c in 0..4 {
+            out[r][c] = m[PERM[r]][PERM[c]];
+        }
+    }
+    out
+}
+
+impl Backend for MpsSimulator {
+    fn name(&self) -> &'static str {
+        BackendKind::Mps { max_bond_dim: self.max_bond_dim }.name()
+    }
+
+    fn n_qubits(&self) -> usize {
+        self.n_qubits
+    }
+
+    fn apply_circuit(&mut self, circuit: &Circuit) {
+        MpsSimulator::apply_circuit(self, circuit);
+    }
+
+    fn probability(&self, outcome: u128) -> f64 {
+        self.amplitude(outcome).norm_sqr()
+    }
+
+    fn probabilities(&self) -> Option<Vec<f64>> {
+        self.amplitudes().map(|amps| amps.iter().map(|a| a.norm_sqr()).collect())
+    }
+
+    fn amplitudes(&self) -> Option<Vec<C64>> {
+        (self.n_qubits <= MAX_DENSE_QUBITS).then(|| self.dense_amplitudes())
+    }
+
+    fn sample(&mut self, n_shots: usize) -> Vec<u128> {
+        MpsSimulator::sample(self, n_shots)
+    }
+
+    fn estimated_fidelity(&self) -> Option<f64> {
+        Some(self.fidelity)
+    }
+
+    fn layer_fidelities(&self) -> Option<Vec<f64>> {
+        Some(self.layer_fidelities.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_rcs_circuit, sampling, Gate, Moment, QuantumSimulator, RcsConfig};
+
+    #[test]
+    fn test_mps_matches_state_vector() {
+        // Includes the random long-range pairs, which go through SWAP chains
+        for entangler in [Gate::CZ, Gate::SYCAMORE_FSIM] {
+            let config = RcsConfig { entangler, ..RcsConfig::seeded(6, 7, 0, 17) };
+            let mut circuit = generate_rcs_circuit(&config);
+            let mut extra = Moment::new();
+            extra.push(Gate::CNOT, vec![6, 1]);
+            circuit.push_moment(extra);
+
+            let mut sv = QuantumSimulator::with_seed(7, 0);
+            sv.apply_circuit(&circuit);
+            let mut mps = MpsSimulator::new(7, 64, 0);
+            mps.apply_circuit(&circuit);
+
+            assert!((mps.fidelity() - 1.0).abs() < 1e-9);
+            let exact = Backend::amplitudes(&sv).unwrap();
+            let dense = Backend::amplitudes(&mps).unwrap();
+            for (i, amp) in exact.iter().enumerate() {
+                assert!((mps.amplitude(i as u128) - amp).norm() < 1e-9);
+                assert!((dense[i] - amp).norm() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_truncation_reduces_fidelity() {
+        let config = RcsConfig::seeded(8, 8, 0, 4);
+        let circuit = generate_rcs_circuit(&config);
+        let mut mps = MpsSimulator::new(8, 2, 0);
+        mps.apply_circuit(&circuit);
+
+        assert!(mps.bond_dims().iter().all(|&d| d <= 2));
+        assert!(mps.fidelity() < 0.99);
+        assert_eq!(mps.layer_fidelities().len(), circuit.moments.len());
+        let product: f64 = mps.layer_fidelities().iter().product();
+        assert!((product - mps.fidelity()).abs() < 1e-12);
+        // Truncated state stays normalized
+        let sum: f64 = Backend::probabilities(&mps).unwrap().iter().sum();
+        assert!((sum - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sequential_sampling_follows_distribution() {
+        let config = RcsConfig::seeded(3, 4, 0, 8);
+        let mut mps = MpsSimulator::new(4, 16, 3);
+        mps.apply_circuit(&generate_rcs_circuit(&config));
+        let probs = Backend::probabilities(&mps).unwrap();
+
+        let shots: Vec<usize> = mps.sample(20000).into_iter().map(|s| s as usize).collect();
+        let counts = sampling::histogram(&shots);
+        for (i, p) in probs.iter().enumerate() {
+            let freq = counts.get(&i).copied().unwrap_or(0) as f64 / 20000.0;
+            assert!((freq - p).abs() < 0.02, "outcome {}: {} vs {}", i, freq, p);
+        }
+    }
+
+    #[test]
+    fn test_large_low_depth_circuit() {
+        let config = RcsConfig::seeded(3, 60, 0, 2);
+        let mut mps = MpsSimulator::new(60, 8, 1);
+        mps.apply_circuit(&generate_rcs_circuit(&config));
+        let shots = mps.sample(4);
+        assert!(shots.iter().all(|&s| s < 1u128 << 60));
+        assert!(shots.iter().all(|&s| mps.probability(s) > 0.0));
+    }
+}
@@ -0,0 +1,944 @@
+//! Gate and decoherence noise models
+//!
+//! Noisy circuits are simulated with Monte-Carlo trajectories: every
+//! trajectory runs the circuit on a pure state vector and inserts randomly
+//! drawn errors after the gates. Sampling a few shots from each of many
+//! trajectories follows the noisy (mixed-state) output distribution, while
+//! the benchmark keeps scoring against the noiseless ideal one.
+//!
+//! Relaxation (T1/T2) acts on every qubit for the duration of each moment,
+//! so idle qubits decohere too. Its Kraus operators are unraveled on the
+//! state vector by picking one operator per qubit with its Born probability.
+//!
+//! Readout errors are classical: they flip bits of already sampled outcomes,
+//! and can be undone on a histogram by inverting the confusion matrix.
+//!
+//! Coherent errors are deterministic miscalibrations. They rewrite the
+//! circuit once, before any trajectory runs, so every shot sees the same
+//! wrong unitary.
+//!
+//! White noise is the cheap alternative to all of the above: an emulated
+//! device draws each shot from the ideal distribution with probability F and
+//! uniformly otherwise, which works on every backend at any size.
+//!
+//! Device profiles with per-qubit and per-pair rates are kept as JSON or
+//! TOML files in the serialized form of [`NoiseModel`] and read with
+//! [`NoiseModel::from_file`], which also returns the file's SHA-256 so a
+//! result can name the exact profile it ran under.
+
+use crate::{Backend, Circuit, Gate, Moment, Operation, QuantumSimulator, C64};
+use crate::gates::{self, Matrix2};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+
+/// Default number of trajectories a noisy run is spread over
+pub const DEFAULT_TRAJECTORIES: usize = 64;
+
+/// Sycamore's single-qubit gate duration in nanoseconds
+pub const SYCAMORE_GATE_TIME_1Q: f64 = 25.0;
+
+/// Sycamore's two-qubit gate duration in nanoseconds
+pub const SYCAMORE_GATE_TIME_2Q: f64 = 12.0;
+
+/// Error rates of a noisy device, noiseless by default
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct NoiseModel {
+    /// Probability of a uniformly random X, Y or Z after each single-qubit gate
+    #[serde(default)]
+    pub depolarizing_1q: f64,
+    /// Probability of a uniformly random non-identity two-qubit Pauli after
+    /// each two-qubit gate
+    #[serde(default)]
+    pub depolarizing_2q: f64,
+    /// Per-qubit single-qubit error probabilities; entry q replaces
+    /// `depolarizing_1q` on qubit q
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub qubit_depolarizing: Vec<f64>,
+    /// Two-qubit error rates and crosstalk of individual pairs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pairs: Vec<PairNoise>,
+    /// Amplitude damping and dephasing over the duration of every moment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relaxation: Option<Relaxation>,
+    /// Bit flips of the measured outcomes, applied to samples after the circuit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readout: Option<ReadoutError>,
+    /// Miscalibrated CZ gates, applied by rewriting the circuit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coherent: Option<CoherentErrors>,
+    /// Global white noise emulating the whole device, in place of the
+    /// physical error model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub white_noise: Option<WhiteNoise>,
+}
+
+/// Fidelity of a white-noise emulated device
+///
+/// Each shot comes from the ideal distribution with probability F and is
+/// uniformly random otherwise, so the expected XEB is F times that of the
+/// ideal samples.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WhiteNoise {
+    /// Fixed circuit fidelity F
+    Fidelity(f64),
+    /// F from the digital error model (see [`digital_fidelity`])
+    GateFidelities {
+        /// Fidelity of each single-qubit gate
+        one_qubit: f64,
+        /// Fidelity of each two-qubit gate
+        two_qubit: f64,
+        /// Fidelity of measuring each qubit
+        readout: f64,
+    },
+}
+
+impl WhiteNoise {
+    /// Circuit fidelity F of the emulated device running `circuit`
+    pub fn fidelity(&self, circuit: &Circuit) -> f64 {
+        match *self {
+            WhiteNoise::Fidelity(f) => f,
+            WhiteNoise::GateFidelities { one_qubit, two_qubit, readout } => {
+                digital_fidelity(circuit, one_qubit, two_qubit, readout)
+            }
+        }
+    }
+
+    /// Check that every fidelity is in [0, 1]
+    pub fn validate(&self) -> Result<(), String> {
+        let fidelities = match *self {
+            WhiteNoise::Fidelity(f) => vec![f],
+            WhiteNoise::GateFidelities { one_qubit, two_qubit, readout } => vec![one_qubit, two_qubit, readout],
+        };
+        if fidelities.iter().all(|f| (0.0..=1.0).contains(f)) {
+            Ok(())
+        } else {
+            Err("white-noise fidelities must be in [0, 1]".to_string())
+        }
+    }
+}
+
+/// Circuit fidelity in the digital error model: the product of one fidelity
+/// per single-qubit gate, per two-qubit gate and per measured qubit
+///
+/// Virtual Z rotations are frame updates and count as perfect.
+pub fn digital_fidelity(circuit: &Circuit, f_1q: f64, f_2q: f64, f_readout: f64) -> f64 {
+    let n_1q = circuit.operations().filter(|op| op.qubits.len() == 1 && !op.gate.is_virtual()).count();
+    let n_2q = circuit.operations().filter(|op| op.qubits.len() == 2).count();
+    f_1q.powi(n_1q as i32) * f_2q.powi(n_2q as i32) * f_readout.powi(circuit.n_qubits as i32)
+}
+
+/// Draw `n_shots` outcomes of a white-noise emulated device with circuit
+/// fidelity `fidelity`
+///
+/// `backend` holds the ideal state; each shot is one of its samples with
+/// probability `fidelity` and a uniformly random bitstring otherwise.
+pub fn sample_white_noise<R: Rng + ?Sized>(
+    backend: &mut dyn Backend,
+    fidelity: f64,
+    n_shots: usize,
+    rng: &mut R,
+) -> Vec<u128> {
+    let n_qubits = backend.n_qubits();
+    let mask = if n_qubits >= 128 { u128::MAX } else { (1u128 << n_qubits) - 1 };
+    let from_ideal: Vec<bool> = (0..n_shots).map(|_| rng.gen_bool(fidelity)).collect();
+    let mut ideal = backend.sample(from_ideal.iter().filter(|&&b| b).count()).into_iter();
+    from_ideal
+        .into_iter()
+        .map(|b| if b { ideal.next().expect("one ideal sample per flagged shot") } else { rng.gen::<u128>() & mask })
+        .collect()
+}
+
+/// Noise of one coupled qubit pair
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairNoise {
+    /// The two qubits, in either order
+    pub qubits: [usize; 2],
+    /// Two-qubit error probability replacing `depolarizing_2q` on this pair
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depolarizing: Option<f64>,
+    /// Conditional phase in radians the pair picks up during every moment
+    /// from residual ZZ coupling, whether or not it is driven
+    #[serde(default)]
+    pub zz_crosstalk: f64,
+}
+
+impl PairNoise {
+    /// Whether the entry describes the pair `a`, `b`
+    fn matches(&self, a: usize, b: usize) -> bool {
+        self.qubits == [a, b] || self.qubits == [b, a]
+    }
+}
+
+/// T1/T2 decoherence with per-qubit times and per-gate durations
+///
+/// All times share one unit, nanoseconds by convention.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Relaxation {
+    /// Energy relaxation time of each qubit
+    pub t1: Vec<f64>,
+    /// Coherence time of each qubit, at most 2·T1
+    pub t2: Vec<f64>,
+    /// Duration of a single-qubit gate
+    #[serde(default = "sycamore_gate_time_1q")]
+    pub gate_time_1q: f64,
+    /// Duration of a two-qubit gate
+    #[serde(default = "sycamore_gate_time_2q")]
+    pub gate_time_2q: f64,
+}
+
+fn sycamore_gate_time_1q() -> f64 {
+    SYCAMORE_GATE_TIME_1Q
+}
+
+fn sycamore_gate_time_2q() -> f64 {
+    SYCAMORE_GATE_TIME_2Q
+}
+
+impl Relaxation {
+    /// The same T1 and T2 on all `n_qubits`, with Sycamore's gate durations
+    pub fn uniform(n_qubits: usize, t1: f64, t2: f64) -> Self {
+        Self {
+            t1: vec![t1; n_qubits],
+            t2: vec![t2; n_qubits],
+            gate_time_1q: SYCAMORE_GATE_TIME_1Q,
+            gate_time_2q: SYCAMORE_GATE_TIME_2Q,
+        }
+    }
+
+    /// Check that the times cover `n_qubits` and are physical
+    pub fn validate(&self, n_qubits: usize) -> Result<(), String> {
+        if self.t1.len() < n_qubits || self.t2.len() < n_qubits {
+            return Err(format!("T1 and T2 must be given for all {} qubits", n_qubits));
+        }
+        for (q, (&t1, &t2)) in self.t1.iter().zip(&self.t2).enumerate() {
+            if !(t1 > 0.0 && t2 > 0.0 && t2 <= 2.0 * t1) {
+                return Err(format!("qubit {}: need T1 > 0 and 0 < T2 <= 2·T1", q));
+            }
+        }
+        if !(self.gate_time_1q >= 0.0 && self.gate_time_2q >= 0.0) {
+            return Err("gate times must be non-negative".to_string());
+        }
+        Ok(())
+    }
+
+    /// Duration of a moment: its slowest gate, with virtual Z rotations taking no time
+    pub fn moment_duration(&self, moment: &Moment) -> f64 {
+        moment
+            .operations
+            .iter()
+            .filter(|op| !op.gate.is_virtual())
+            .map(|op| if op.qubits.len() == 1 { self.gate_time_1q } else { self.gate_time_2q })
+            .fold(0.0, f64::max)
+    }
+
+    /// Process fidelity of relaxing `qubit` for `duration`: Σ|Tr K|²/4
+    pub fn process_fidelity(&self, qubit: usize, duration: f64) -> f64 {
+        let kraus = self.kraus(qubit, duration);
+        kraus.iter().map(|k| (k[0][0] + k[1][1]).norm_sqr()).sum::<f64>() / 4.0
+    }
+
+    /// Kraus operators of amplitude damping followed by pure dephasing of
+    /// `qubit` over `duration`
+    ///
+    /// Populations relax as exp(-t/T1) and coherences as exp(-t/T2).
+    pub fn kraus(&self, qubit: usize, duration: f64) -> [Matrix2; 3] {
+        let (t1, t2) = (self.t1[qubit], self.t2[qubit]);
+        let gamma = 1.0 - (-duration / t1).exp();
+        // Pure dephasing rate 1/Tφ = 1/T2 - 1/(2·T1)
+        let lambda = 1.0 - (-2.0 * duration * (1.0 / t2 - 0.5 / t1).max(0.0)).exp();
+        let zero = C64::new(0.0, 0.0);
+        let real = |x: f64| C64::new(x.sqrt(), 0.0);
+        [
+            [[real(1.0), zero], [zero, real((1.0 - gamma) * (1.0 - lambda))]],
+            [[zero, real(gamma)], [zero, zero]],
+            [[zero, zero], [zero, real(lambda * (1.0 - gamma))]],
+        ]
+    }
+}
+
+/// Coherent miscalibration of CZ gates
+///
+/// Every CZ on a pair becomes CPhase(π + δ) followed by stray Rz(ζ) phases
+/// on both qubits. δ (per pair) and ζ (per pair and qubit) are drawn once,
+/// uniformly from offset ± spread, so the same miscalibration repeats in
+/// every layer. Other entanglers are left untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoherentErrors {
+    /// Mean conditional-phase offset δ in radians
+    pub cphase_offset: f64,
+    /// Half-width of the per-pair spread of δ
+    pub cphase_spread: f64,
+    /// Mean stray Z phase ζ in radians
+    pub z_offset: f64,
+    /// Half-width of the per-pair spread of ζ
+    pub z_spread: f64,
+}
+
+impl CoherentErrors {
+    /// Rewrite every CZ of `circuit` with its miscalibrated version, drawing
+    /// the per-pair offsets from `seed`
+    pub fn apply(&self, circuit: &Circuit, seed: u64) -> Circuit {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut offsets: BTreeMap<(usize, usize), [f64; 3]> = BTreeMap::new();
+        let mut out = Circuit::new(circuit.n_qubits);
+        for moment in &circuit.moments {
+            let mut layer = Moment::new();
+            for op in &moment.operations {
+                if op.gate != Gate::CZ {
+                    layer.operations.push(op.clone());
+                    continue;
+                }
+                let (a, b) = (op.qubits[0], op.qubits[1]);
+                let pair = (a.min(b), a.max(b));
+                let [delta, z_lo, z_hi] = *offsets.entry(pair).or_insert_with(|| {
+                    let mut draw = |offset: f64, spread: f64| offset + spread * (2.0 * rng.gen::<f64>() - 1.0);
+                    [
+                        draw(self.cphase_offset, self.cphase_spread),
+                        draw(self.z_offset, self.z_spread),
+                        draw(self.z_offset, self.z_spread),
+                    ]
+                });
+                layer.push(Gate::CPhase(PI + delta), vec![a, b]);
+                for (q, z) in [(pair.0, z_lo), (pair.1, z_hi)] {
+                    if z != 0.0 {
+                        layer.push(Gate::Rz(z), vec![q]);
+                    }
+                }
+            }
+            out.push_moment(layer);
+        }
+        out
+    }
+}
+
+/// Asymmetric per-qubit measurement errors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadoutError {
+    /// Probability that qubit q in |0⟩ is read as 1
+    pub p01: Vec<f64>,
+    /// Probability that qubit q in |1⟩ is read as 0
+    pub p10: Vec<f64>,
+}
+
+impl ReadoutError {
+    /// The same flip probabilities on all `n_qubits`
+    pub fn uniform(n_qubits: usize, p01: f64, p10: f64) -> Self {
+        Self { p01: vec![p01; n_qubits], p10: vec![p10; n_qubits] }
+    }
+
+    /// Check that the probabilities cover `n_qubits` and can be mitigated
+    pub fn validate(&self, n_qubits: usize) -> Result<(), String> {
+        if self.p01.len() < n_qubits || self.p10.len() < n_qubits {
+            return Err(format!("readout errors must be given for all {} qubits", n_qubits));
+        }
+        for (q, (&p01, &p10)) in self.p01.iter().zip(&self.p10).enumerate() {
+            if !(p01 >= 0.0 && p10 >= 0.0 && p01 + p10 < 1.0) {
+                return Err(format!("qubit {}: need readout errors >= 0 with p01 + p10 < 1", q));
+            }
+        }
+        Ok(())
+    }
+
+    /// Confusion matrix of `qubit`: entry [m][t] is P(read m | state t)
+    pub fn confusion_matrix(&self, qubit: usize) -> [[f64; 2]; 2] {
+        let (p01, p10) = (self.p01[qubit], self.p10[qubit]);
+        [[1.0 - p01, p10], [p01, 1.0 - p10]]
+    }
+
+    /// Flip the bits of every outcome in `samples` with the readout error rates
+    pub fn apply<R: Rng + ?Sized>(&self, samples: &mut [u128], n_qubits: usize, rng: &mut R) {
+        for s in samples.iter_mut() {
+            for q in 0..n_qubits {
+                let p = if *s >> q & 1 == 0 { self.p01[q] } else { self.p10[q] };
+                if p > 0.0 && rng.gen_bool(p) {
+                    *s ^= 1 << q;
+                }
+            }
+        }
+    }
+
+    /// Distribution of read-out outcomes given the distribution `probs` of
+    /// the measured state
+    pub fn confuse(&self, probs: &[f64]) -> Vec<f64> {
+        let mut out = probs.to_vec();
+        for q in 0..out.len().trailing_zeros() as usize {
+            apply_to_qubit(&mut out, q, self.confusion_matrix(q));
+        }
+        out
+    }
+
+    /// Mitigated quasi-probabilities of the state before readout, from the
+    /// histogram of read-out outcomes on `n_qubits`
+    ///
+    /// Applies the inverse of the tensor-product confusion matrix to the
+    /// empirical distribution. Entries may be slightly negative; they still
+    /// sum to one.
+    pub fn mitigate(&self, counts: &BTreeMap<usize, usize>, n_qubits: usize) -> Vec<f64> {
+        let total: usize = counts.values().sum();
+        let mut quasi = vec![0.0; 1 << n_qubits];
+        for (&outcome, &count) in counts {
+            quasi[outcome] = count as f64 / total.max(1) as f64;
+        }
+        for q in 0..n_qubits {
+            let [[a, b], [c, d]] = self.confusion_matrix(q);
+            let det = a * d - b * c;
+            apply_to_qubit(&mut quasi, q, [[d / det, -b / det], [-c / det, a / det]]);
+        }
+        quasi
+    }
+}
+
+/// Multiply the axis of `qubit` of a dense distribution by the 2x2 matrix `m`
+fn apply_to_qubit(probs: &mut [f64], qubit: usize, m: [[f64; 2]; 2]) {
+    let stride = 1 << qubit;
+    for chunk in probs.chunks_mut(2 * stride) {
+        let (zeros, ones) = chunk.split_at_mut(stride);
+        for (p0, p1) in zeros.iter_mut().zip(ones) {
+            let (x, y) = (*p0, *p1);
+            *p0 = m[0][0] * x + m[0][1] * y;
+            *p1 = m[1][0] * x + m[1][1] * y;
+        }
+    }
+}
+
+impl NoiseModel {
+    /// Depolarizing noise with the given single- and two-qubit error probabilities
+    pub fn depolarizing(p_1q: f64, p_2q: f64) -> Self {
+        assert!((0.0..=1.0).contains(&p_1q), "error probability must be in [0, 1]");
+        assert!((0.0..=1.0).contains(&p_2q), "error probability must be in [0, 1]");
+        Self { depolarizing_1q: p_1q, depolarizing_2q: p_2q, ..Self::default() }
+    }
+
+    /// Add T1/T2 relaxation to the model
+    pub fn with_relaxation(mut self, relaxation: Relaxation) -> Self {
+        self.relaxation = Some(relaxation);
+        self
+    }
+
+    /// Add readout errors to the model
+    pub fn with_readout(mut self, readout: ReadoutError) -> Self {
+        self.readout = Some(readout);
+        self
+    }
+
+    /// Add coherent CZ miscalibration to the model
+    pub fn with_coherent(mut self, coherent: CoherentErrors) -> Self {
+        self.coherent = Some(coherent);
+        self
+    }
+
+    /// Load a device profile from a JSON or TOML file, chosen by its
+    /// extension, and return it with the hex SHA-256 of the file contents
+    pub fn from_file(path: &Path) -> Result<(Self, String), String> {
+        let bytes = fs::read(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        let text = std::str::from_utf8(&bytes).map_err(|_| format!("{} is not valid UTF-8", path.display()))?;
+        let model = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(text).map_err(|e| e.to_string()),
+            Some("json") => serde_json::from_str(text).map_err(|e| e.to_string()),
+            _ => Err("expected a .json or .toml file".to_string()),
+        };
+        let model = model.map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok((model, sha256_hex(&bytes)))
+    }
+
+    /// Single-qubit error probability of `qubit`
+    pub fn error_1q(&self, qubit: usize) -> f64 {
+        self.qubit_depolarizing.get(qubit).copied().unwrap_or(self.depolarizing_1q)
+    }
+
+    /// Two-qubit error probability of the pair `a`, `b`
+    pub fn error_2q(&self, a: usize, b: usize) -> f64 {
+        self.pairs
+            .iter()
+            .find(|pair| pair.matches(a, b))
+            .and_then(|pair| pair.depolarizing)
+            .unwrap_or(self.depolarizing_2q)
+    }
+
+    /// Circuit fidelity of `circuit` predicted by the digital error model
+    ///
+    /// Multiplies the fidelity 1 - p of every gate (virtual Z rotations are
+    /// perfect), 1 - (p01 + p10)/2 of every measured qubit and the process
+    /// fidelity of each qubit's relaxation over every moment. Coherent errors
+    /// and crosstalk are not counted. A white-noise model predicts its own F.
+    pub fn predicted_fidelity(&self, circuit: &Circuit) -> f64 {
+        if let Some(white_noise) = &self.white_noise {
+            return white_noise.fidelity(circuit);
+        }
+        let mut fidelity = 1.0;
+        for op in circuit.operations() {
+            fidelity *= match op.qubits[..] {
+                [q] if !op.gate.is_virtual() => 1.0 - self.error_1q(q),
+                [a, b] => 1.0 - self.error_2q(a, b),
+                _ => 1.0,
+            };
+        }
+        if let Some(relaxation) = &self.relaxation {
+            for moment in &circuit.moments {
+                let duration = relaxation.moment_duration(moment);
+                for q in 0..circuit.n_qubits {
+                    fidelity *= relaxation.process_fidelity(q, duration);
+                }
+            }
+        }
+        if let Some(readout) = &self.readout {
+            for q in 0..circuit.n_qubits {
+                fidelity *= 1.0 - (readout.p01[q] + readout.p10[q]) / 2.0;
+            }
+        }
+        fidelity
+    }
+
+    /// Pairs with ZZ crosstalk and their conditional phase per moment
+    pub fn crosstalk(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        self.pairs
+            .iter()
+            .filter(|pair| pair.zz_crosstalk != 0.0)
+            .map(|pair| (pair.qubits[0], pair.qubits[1], pair.zz_crosstalk))
+    }
+
+    /// Whether the model inserts random errors that need trajectories or a density matrix
+    pub fn is_stochastic(&self) -> bool {
+        self.depolarizing_1q > 0.0
+            || self.depolarizing_2q > 0.0
+            || self.qubit_depolarizing.iter().any(|&p| p > 0.0)
+            || self.pairs.iter().any(|pair| pair.depolarizing.unwrap_or(0.0) > 0.0)
+            || self.relaxation.is_some()
+    }
+
+    /// Whether the model acts on the quantum state, not only on readout
+    pub fn has_circuit_noise(&self) -> bool {
+        self.is_stochastic() || self.coherent.is_some() || self.crosstalk().next().is_some()
+    }
+
+    /// The circuit the noisy device actually runs: `circuit` with the
+    /// coherent errors drawn from `seed`, or an unchanged copy
+    pub fn device_circuit(&self, circuit: &Circuit, seed: u64) -> Circuit {
+        match &self.coherent {
+            Some(coherent) => coherent.apply(circuit, seed),
+            None => circuit.clone(),
+        }
+    }
+
+    /// Whether the model never inserts an error
+    pub fn is_noiseless(&self) -> bool {
+        !self.has_circuit_noise() && self.readout.is_none() && self.white_noise.is_none()
+    }
+
+    /// Check that the model is physical for a circuit on `n_qubits`
+    pub fn validate(&self, n_qubits: usize) -> Result<(), String> {
+        let probabilities = [self.depolarizing_1q, self.depolarizing_2q]
+            .into_iter()
+            .chain(self.qubit_depolarizing.iter().copied())
+            .chain(self.pairs.iter().filter_map(|pair| pair.depolarizing));
+        for p in probabilities {
+            if !(0.0..=1.0).contains(&p) {
+                return Err("depolarizing probabilities must be in [0, 1]".to_string());
+            }
+        }
+        for (i, pair) in self.pairs.iter().enumerate() {
+            let [a, b] = pair.qubits;
+            if a == b || a.max(b) >= n_qubits {
+                return Err(format!("pair {:?} is not two distinct qubits of {}", pair.qubits, n_qubits));
+            }
+            if self.pairs[..i].iter().any(|other| other.matches(a, b)) {
+                return Err(format!("pair {:?} is listed twice", pair.qubits));
+            }
+            if !pair.zz_crosstalk.is_finite() {
+                return Err(format!("pair {:?}: crosstalk phase must be finite", pair.qubits));
+            }
+        }
+        if let Some(relaxation) = &self.relaxation {
+            relaxation.validate(n_qubits)?;
+        }
+        if let Some(white_noise) = &self.white_noise {
+            white_noise.validate()?;
+            if self.has_circuit_noise() || self.readout.is_some() {
+                return Err("white noise replaces the physical error model and cannot be combined with it".to_string());
+            }
+        }
+        match &self.readout {
+            Some(readout) => readout.validate(n_qubits),
+            None => Ok(()),
+        }
+    }
+
+    /// Insert the errors that follow `op` into one trajectory
+    pub fn apply_after<R: Rng + ?Sized>(&self, sim: &mut QuantumSimulator, op: &Operation, rng: &mut R) {
+        let p = match op.qubits[..] {
+            [q] => self.error_1q(q),
+            [a, b] => self.error_2q(a, b),
+            _ => 0.0,
+        };
+        if p == 0.0 || !rng.gen_bool(p) {
+            return;
+        }
+        // Index 1..4^k of the Pauli string, base 4 per qubit: 0 = I, 1 = X, 2 = Y, 3 = Z
+        let mut paulis = rng.gen_range(1..1 << (2 * op.qubits.len()));
+        for &q in &op.qubits {
+            if let Some(m) = pauli(paulis & 3) {
+                sim.apply_1q(q, m);
+            }
+            paulis >>= 2;
+        }
+    }
+
+    /// Relax every qubit of one trajectory for `duration`
+    pub fn apply_relaxation<R: Rng + ?Sized>(&self, sim: &mut QuantumSimulator, duration: f64, rng: &mut R) {
+        let Some(relaxation) = &self.relaxation else { return };
+        if duration <= 0.0 {
+            return;
+        }
+        for q in 0..sim.n_qubits() {
+            apply_kraus(sim, q, &relaxation.kraus(q, duration), rng);
+        }
+    }
+
+    /// Run one trajectory of `circuit` on `sim`, drawing errors from `rng`
+    pub fn apply_circuit<R: Rng + ?Sized>(&self, sim: &mut QuantumSimulator, circuit: &Circuit, rng: &mut R) {
+        assert_eq!(circuit.n_qubits, sim.n_qubits(), "circuit and simulator qubit counts differ");
+        for moment in &circuit.moments {
+            for op in &moment.operations {
+                sim.apply_operation(op);
+                self.apply_after(sim, op, rng);
+            }
+            for (a, b, phi) in self.crosstalk() {
+                sim.cphase(a, b, phi);
+            }
+            if let Some(relaxation) = &self.relaxation {
+                self.apply_relaxation(sim, relaxation.moment_duration(moment), rng);
+            }
+        }
+    }
+}
+
+/// Lowercase hex SHA-256 of `bytes`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Apply one Kraus operator of a single-qubit channel to `qubit`, chosen
+/// with probability ‖Kψ‖², and renormalize
+fn apply_kraus<R: Rng + ?Sized>(sim: &mut QuantumSimulator, qubit: usize, kraus: &[Matrix2], rng: &mut R) {
+    let rho = sim.reduced_density_matrix(qubit);
+    // ‖Kψ‖² = Tr(K ρ K†)
+    let weights: Vec<f64> = kraus
+        .iter()
+        .map(|k| {
+            let mut p = 0.0;
+            for row in k {
+                for b in 0..2 {
+                    for c in 0..2 {
+                        p += (row[b] * rho[b][c] * row[c].conj()).re;
+                    }
+                }
+            }
+            p.max(0.0)
+        })
+        .collect();
+    let total: f64 = weights.iter().sum();
+    let mut r = rng.gen::<f64>() * total;
+    let chosen = weights.iter().position(|&w| {
+        r -= w;
+        r < 0.0
+    });
+    let i = chosen.unwrap_or(kraus.len() - 1);
+    let scale = 1.0 / weights[i].sqrt();
+    sim.apply_1q(qubit, kraus[i].map(|row| row.map(|x| x * scale)));
+}
+
+/// Pauli matrix for index 1 = X, 2 = Y, 3 = Z; `None` for the identity
+fn pauli(index: usize) -> Option<Matrix2> {
+    match index {
+        1 => Some(gates::x()),
+        2 => Some(gates::y()),
+        3 => Some(gates::z()),
+        _ => None,
+    }
+}
+
+/// Draw `n_shots` samples of `circuit` under `noise`, spread evenly over
+/// `n_trajectories` independent trajectories
+///
+/// Only the stochastic noise is simulated: coherent errors must already be
+/// part of `circuit` (see [`NoiseModel::device_circuit`]) and readout errors
+/// are left to the caller. Without stochastic noise all shots come from a
+/// single trajectory.
+///
+/// Trajectory t draws its errors and shots from stream t of `seed`, so the
+/// result is reproducible and independent of `threads` (kernel thread count).
+pub fn sample_trajectories(
+    circuit: &Circuit,
+    noise: &NoiseModel,
+    n_shots: usize,
+    n_trajectories: usize,
+    seed: u64,
+    threads: usize,
+) -> Vec<u128> {
+    let mut sim = QuantumSimulator::with_seeds(circuit.n_qubits, 0, 0);
+    sim.set_threads(threads);
+
+    let mut samples = Vec::with_capacity(n_shots);
+    for (t, shots) in trajectory_shots(noise, n_shots, n_trajectories).into_iter().enumerate() {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        rng.set_stream(t as u64);
+        sim.reset();
+        sim.reseed_sampling(rng.gen());
+        noise.apply_circuit(&mut sim, circuit, &mut rng);
+        samples.extend(sim.sample(shots).into_iter().map(|s| s as u128));
+    }
+    samples
+}
+
+/// Shots of each trajectory, in order, when [`sample_trajectories`] spreads
+/// `n_shots` over `n_trajectories` trajectories of `noise`
+pub fn trajectory_shots(noise: &NoiseModel, n_shots: usize, n_trajectories: usize) -> Vec<usize> {
+    let n_trajectories = if noise.is_stochastic() { n_trajectories.clamp(1, n_shots.max(1)) } else { 1 };
+    // The first n_shots % n_trajectories trajectories take one extra shot
+    (0..n_trajectories).map(|t| n_shots / n_trajectories + usize::from(t < n_shots % n_trajectories)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_rcs_with_config, sampling, Gate, Moment, RcsConfig};
+
+    #[test]
+    fn test_single_qubit_depolarizing_statistics() {
+        // X|0⟩ = |1⟩; after p = 3/4 depolarizing the qubit is maximally mixed
+        let mut circuit = Circuit::new(1);
+        let mut layer = Moment::new();
+        layer.push(Gate::X, vec![0]);
+        circuit.push_moment(layer);
+
+        let noise = NoiseModel::depolarizing(0.75, 0.0);
+        let shots: Vec<usize> = sample_trajectories(&circuit, &noise, 8000, 8000, 5, 1)
+            .into_iter()
+            .map(|s| s as usize)
+            .collect();
+        let counts = sampling::histogram(&shots);
+        assert!((counts[&0] as f64 / 8000.0 - 0.5).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_relaxation_decays_idle_qubits() {
+        // Qubit 0 is excited in the first moment and idles through the second
+        let mut circuit = Circuit::new(2);
+        for q in 0..2 {
+            let mut layer = Moment::new();
+            layer.push(Gate::X, vec![q]);
+            circuit.push_moment(layer);
+        }
+        let mut relaxation = Relaxation::uniform(2, 100.0, 200.0);
+        relaxation.gate_time_1q = 50.0;
+        let noise = NoiseModel::default().with_relaxation(relaxation);
+
+        let shots = sample_trajectories(&circuit, &noise, 8000, 8000, 6, 1);
+        let excited = |q: usize| shots.iter().filter(|&&s| s >> q & 1 == 1).count() as f64 / 8000.0;
+        assert!((excited(0) - (-1.0f64).exp()).abs() < 0.03);
+        assert!((excited(1) - (-0.5f64).exp()).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_dephasing_kraus() {
+        // Pure dephasing with T2 = duration shrinks coherences by 1/e
+        let relaxation = Relaxation::uniform(1, 1e12, 40.0);
+        let kraus = relaxation.kraus(0, 40.0);
+        let coherence: f64 = kraus.iter().map(|k| (k[0][0] * k[1][1].conj()).re).sum();
+        assert!((coherence - (-1.0f64).exp()).abs() < 1e-9);
+        // Completeness: Σ K†K = I
+        for (r, c) in [(0, 0), (0, 1), (1, 1)] {
+            let sum: C64 = kraus.iter().map(|k| k[0][r].conj() * k[0][c] + k[1][r].conj() * k[1][c]).sum();
+            let expected = if r == c { 1.0 } else { 0.0 };
+            assert!((sum - C64::new(expected, 0.0)).norm() < 1e-12);
+        }
+        assert!(Relaxation::uniform(1, 10.0, 30.0).validate(1).is_err());
+    }
+
+    #[test]
+    fn test_readout_flips_and_mitigation() {
+        let readout = ReadoutError { p01: vec![0.02, 0.1], p10: vec![0.08, 0.2] };
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        // True state |01⟩: qubit 0 in |1⟩, qubit 1 in |0⟩
+        let mut samples = vec![0b01u128; 20000];
+        readout.apply(&mut samples, 2, &mut rng);
+
+        let shots: Vec<usize> = samples.iter().map(|&s| s as usize).collect();
+        let counts = sampling::histogram(&shots);
+        let expected = readout.confuse(&[0.0, 1.0, 0.0, 0.0]);
+        for (outcome, p) in expected.iter().enumerate() {
+            let freq = counts.get(&outcome).copied().unwrap_or(0) as f64 / 20000.0;
+            assert!((freq - p).abs() < 0.01);
+        }
+
+        let mitigated = readout.mitigate(&counts, 2);
+        assert!((mitigated[1] - 1.0).abs() < 0.02);
+        assert!((mitigated.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(ReadoutError::uniform(1, 0.6, 0.5).validate(1).is_err());
+    }
+
+    #[test]
+    fn test_coherent_errors_fixed_per_pair() {
+        let mut circuit = Circuit::new(3);
+        for _ in 0..2 {
+            let mut layer = Moment::new();
+            layer.push(Gate::CZ, vec![0, 1]);
+            layer.push(Gate::H, vec![2]);
+            circuit.push_moment(layer);
+            let mut layer = Moment::new();
+            layer.push(Gate::CZ, vec![2, 1]);
+            circuit.push_moment(layer);
+        }
+        let coherent = CoherentErrors { cphase_offset: 0.1, cphase_spread: 0.05, z_offset: 0.0, z_spread: 0.02 };
+        let noisy = coherent.apply(&circuit, 4);
+
+        let phases: Vec<f64> = noisy
+            .operations()
+            .filter_map(|op| match op.gate {
+                Gate::CPhase(phi) => Some(phi - PI),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(phases.len(), 4);
+        assert_eq!(phases[0], phases[2]);
+        assert_eq!(phases[1], phases[3]);
+        assert_ne!(phases[0], phases[1]);
+        assert!(phases.iter().all(|d| (d - 0.1).abs() <= 0.05));
+        assert_eq!(noisy.operations().filter(|op| matches!(op.gate, Gate::Rz(_))).count(), 8);
+        assert_eq!(noisy.operations().filter(|op| op.gate == Gate::H).count(), 2);
+    }
+
+    #[test]
+    fn test_device_profile_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("noise_models/example_device.toml");
+        let (model, hash) = NoiseModel::from_file(&path).unwrap();
+        assert_eq!(hash, sha256_hex(&fs::read(&path).unwrap()));
+        assert_eq!(hash.len(), 64);
+        assert!(model.validate(6).is_ok());
+        assert!(model.validate(7).is_err());
+        assert_eq!(model.error_1q(3), 0.0021);
+        assert_eq!(model.error_2q(2, 1), 0.0071);
+        assert_eq!(model.error_2q(0, 5), model.depolarizing_2q);
+        assert_eq!(model.crosstalk().count(), 3);
+
+        // The JSON form of the same model reads back identically
+        let json_path = std::env::temp_dir().join(format!("noise_profile_{}.json", std::process::id()));
+        fs::write(&json_path, serde_json::to_string(&model).unwrap()).unwrap();
+        let (parsed, _) = NoiseModel::from_file(&json_path).unwrap();
+        fs::remove_file(&json_path).unwrap();
+        assert_eq!(parsed, model);
+    }
+
+    #[test]
+    fn test_zz_crosstalk() {
+        // A crosstalk phase of π after each moment acts as a CZ: H⊗H, CZ, H
+        // on qubit 1 prepares a Bell state, which the second CZ leaves alone
+        let mut circuit = Circuit::new(2);
+        let mut layer = Moment::new();
+        layer.push(Gate::H, vec![0]);
+        layer.push(Gate::H, vec![1]);
+        circuit.push_moment(layer);
+        let mut layer = Moment::new();
+        layer.push(Gate::H, vec![1]);
+        circuit.push_moment(layer);
+        let noise = NoiseModel {
+            pairs: vec![PairNoise { qubits: [1, 0], depolarizing: None, zz_crosstalk: PI }],
+            ..NoiseModel::default()
+        };
+        assert!(noise.has_circuit_noise() && !noise.is_stochastic());
+
+        let shots = sample_trajectories(&circuit, &noise, 1000, 64, 2, 1);
+        assert!(shots.iter().all(|&s| s == 0 || s == 3));
+        let mut dm = crate::DensityMatrixSimulator::with_noise(2, noise, 0);
+        dm.apply_circuit(&circuit);
+        for (p, expected) in dm.probabilities().iter().zip([0.5, 0.0, 0.0, 0.5]) {
+            assert!((p - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_white_noise_recovers_fidelity() {
+        let config = RcsConfig::seeded(12, 8, 20000, 31);
+        let circuit = crate::generate_rcs_circuit(&config);
+        let mut sim = QuantumSimulator::with_seed(8, 0);
+        sim.apply_circuit(&circuit);
+        let ideal = sim.probabilities();
+        let ideal_xeb = crate::expected_xeb(&ideal, &ideal);
+
+        for fidelity in [0.0, 0.3, 0.8] {
+            let noise = NoiseModel { white_noise: Some(WhiteNoise::Fidelity(fidelity)), ..NoiseModel::default() };
+            let xeb = run_rcs_with_config(&RcsConfig { noise, ..config.clone() });
+            // Standard error of 2ⁿ·p(x) under Porter-Thomas is about 1/√shots
+            assert!((xeb - fidelity * ideal_xeb).abs() < 4.0 / 20000f64.sqrt(), "{} vs {}", xeb, fidelity);
+        }
+
+        // Every shot from the ideal distribution reproduces the noiseless run
+        let perfect = NoiseModel { white_noise: Some(WhiteNoise::Fidelity(1.0)), ..NoiseModel::default() };
+        let noiseless = RcsConfig { n_samples: 2000, ..config.clone() };
+        assert_eq!(
+            run_rcs_with_config(&RcsConfig { noise: perfect, ..noiseless.clone() }),
+            run_rcs_with_config(&noiseless)
+        );
+
+        // Per-gate fidelities multiply over every gate and the 8 readouts
+        let count = |arity: usize| circuit.operations().filter(|op| op.qubits.len() == arity).count() as i32;
+        let gates = WhiteNoise::GateFidelities { one_qubit: 0.999, two_qubit: 0.99, readout: 0.98 };
+        let expected = 0.999f64.powi(count(1)) * 0.99f64.powi(count(2)) * 0.98f64.powi(8);
+        assert!((gates.fidelity(&circuit) - expected).abs() < 1e-12);
+        let mixed = NoiseModel { white_noise: Some(gates), ..NoiseModel::depolarizing(0.01, 0.0) };
+        assert!(mixed.validate(8).is_err());
+    }
+
+    #[test]
+    fn test_digital_error_model_prediction() {
+        let config = RcsConfig::seeded(8, 6, 0, 17);
+        let circuit = crate::generate_rcs_circuit(&config);
+        let noise = NoiseModel::depolarizing(0.002, 0.01).with_readout(ReadoutError::uniform(6, 0.01, 0.03));
+        assert!((noise.predicted_fidelity(&circuit) - digital_fidelity(&circuit, 0.998, 0.99, 0.98)).abs() < 1e-12);
+
+        // Relaxation of |1⟩ over one T1 with T2 = 2·T1: populations keep
+        // 1 - γ, coherences √(1 - γ)
+        let relaxation = Relaxation::uniform(1, 50.0, 100.0);
+        let gamma = 1.0 - (-1.0f64).exp();
+        let expected = (2.0 - gamma + 2.0 * (1.0 - gamma).sqrt()) / 4.0;
+        assert!((relaxation.process_fidelity(0, 50.0) - expected).abs() < 1e-12);
+
+        // The prediction tracks the exact XEB of the noisy density matrix
+        let noise = NoiseModel::depolarizing(0.003, 0.015).with_relaxation(Relaxation::uniform(6, 20000.0, 15000.0));
+        let mut sim = QuantumSimulator::with_seed(6, 0);
+        sim.apply_circuit(&circuit);
+        let ideal = sim.probabilities();
+        let mut dm = crate::DensityMatrixSimulator::with_noise(6, noise.clone(), 0);
+        dm.apply_circuit(&circuit);
+        let exact = crate::expected_xeb(&ideal, &dm.probabilities()) / crate::expected_xeb(&ideal, &ideal);
+        let predicted = noise.predicted_fidelity(&circuit);
+        assert!((exact - predicted).abs() < 0.05, "{} vs {}", exact, predicted);
+    }
+
+    #[test]
+    fn test_noise_lowers_xeb() {
+        let ideal = RcsConfig::seeded(8, 8, 8000, 21);
+        let noisy = RcsConfig { noise: NoiseModel::depolarizing(0.002, 0.02), ..ideal.clone() };
+        let ideal_xeb = run_rcs_with_config(&ideal);
+        let noisy_xeb = run_rcs_with_config(&noisy);
+        assert!(noisy_xeb < ideal_xeb - 0.1, "{} vs {}", noisy_xeb, ideal_xeb);
+        assert!(noisy_xeb > 0.1, "{}", noisy_xeb);
+
+        // A coherent conditional-phase error also costs fidelity
+        let coherent = CoherentErrors { cphase_offset: 0.4, ..CoherentErrors::default() };
+        let miscalibrated = RcsConfig { noise: NoiseModel::default().with_coherent(coherent), ..ideal.clone() };
+        let coherent_xeb = run_rcs_with_config(&miscalibrated);
+        assert!(coherent_xeb < ideal_xeb - 0.1, "{} vs {}", coherent_xeb, ideal_xeb);
+
+        // Zero error rates reproduce the noiseless distribution
+        let silent = RcsConfig { noise: NoiseModel::default(), ..ideal };
+        assert_eq!(run_rcs_with_config(&silent), ideal_xeb);
+    }
+}
@@ -0,0 +1,166 @@
+//! Porter-Thomas and anticoncentration diagnostics
+//!
+//! Linear XEB reads as a fidelity only if the ideal output distribution is
+//! Porter-Thomas: the scaled probabilities N·p (N = 2ⁿ) are distributed as
+//! Exp(1), so the collision probability N·Σp² is 2N/(N+1). Shallow circuits
+//! are still concentrated on few outcomes and score well above that. The
+//! Kolmogorov-Smirnov test treats the N values N·p as independent draws,
+//! which they are only approximately, since they sum to N.
+
+use crate::{generate_rcs_circuit, QuantumSimulator, RcsConfig};
+use serde::{Deserialize, Serialize};
+
+/// Width of the histogram bins of N·p
+pub const HISTOGRAM_BIN_WIDTH: f64 = 0.5;
+
+/// Number of histogram bins; the last one is open-ended
+pub const HISTOGRAM_BINS: usize = 12;
+
+/// Default relative excess of the collision probability over Porter-Thomas
+/// that still counts as anticoncentrated
+pub const ANTICONCENTRATION_TOLERANCE: f64 = 0.05;
+
+/// Comparison of an ideal output distribution with Porter-Thomas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PorterThomasReport {
+    /// Collision probability N·Σp²
+    pub collision: f64,
+    /// Collision probability 2N/(N+1) of Porter-Thomas
+    pub porter_thomas_collision: f64,
+    /// Largest distance between the empirical CDF of N·p and 1 - e⁻ˣ
+    pub ks_statistic: f64,
+    /// Asymptotic p-value of the Kolmogorov-Smirnov statistic
+    pub ks_p_value: f64,
+    /// Counts of N·p in bins of [`HISTOGRAM_BIN_WIDTH`] from 0
+    pub histogram: Vec<usize>,
+    /// Counts Porter-Thomas predicts for the same bins
+    pub expected: Vec<f64>,
+}
+
+impl PorterThomasReport {
+    /// Diagnose the ideal distribution `probs` over 2ⁿ outcomes
+    pub fn new(probs: &[f64]) -> Self {
+        let n = probs.len() as f64;
+        let mut scaled: Vec<f64> = probs.iter().map(|p| n * p).collect();
+        scaled.sort_by(f64::total_cmp);
+
+        // D = sup |F_N(x) - F(x)|, checked on both sides of every step
+        let cdf = |x: f64| 1.0 - (-x).exp();
+        let ks_statistic = scaled
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| (cdf(x) - i as f64 / n).max((i + 1) as f64 / n - cdf(x)))
+            .fold(0.0, f64::max);
+
+        let mut histogram = vec![0; HISTOGRAM_BINS];
+        for x in &scaled {
+            histogram[((x / HISTOGRAM_BIN_WIDTH) as usize).min(HISTOGRAM_BINS - 1)] += 1;
+        }
+        let expected = (0..HISTOGRAM_BINS)
+            .map(|b| {
+                let lo = b as f64 * HISTOGRAM_BIN_WIDTH;
+                let upper = if b + 1 < HISTOGRAM_BINS { (-(lo + HISTOGRAM_BIN_WIDTH)).exp() } else { 0.0 };
+                n * ((-lo).exp() - upper)
+            })
+            .collect();
+
+        Self {
+            collision: scaled.iter().map(|x| x * x).sum::<f64>() / n,
+            porter_thomas_collision: 2.0 * n / (n + 1.0),
+            ks_statistic,
+            ks_p_value: kolmogorov_p_value(ks_statistic, n),
+            histogram,
+            expected,
+        }
+    }
+
+    /// Whether the collision probability is within `tolerance` of Porter-Thomas
+    pub fn anticoncentrated(&self, tolerance: f64) -> bool {
+        self.collision <= (1.0 + tolerance) * self.porter_thomas_collision
+    }
+}
+
+/// Probability that the KS statistic of `n` draws exceeds `d`, from the
+/// Kolmogorov distribution with Stephens' finite-size correction
+fn kolmogorov_p_value(d: f64, n: f64) -> f64 {
+    let lambda = (n.sqrt() + 0.12 + 0.11 / n.sqrt()) * d;
+    if lambda < 0.2 {
+        return 1.0;
+    }
+    // Q(λ) = 2 Σ (-1)^(j-1) exp(-2 j² λ²)
+    let q: f64 = (1..=100)
+        .map(|j| {
+            let sign = if j % 2 == 1 { 1.0 } else { -1.0 };
+            sign * (-2.0 * (j * j) as f64 * lambda * lambda).exp()
+        })
+        .sum();
+    (2.0 * q).clamp(0.0, 1.0)
+}
+
+/// Mean collision probability of `n_instances` ideal circuits of `template`'s
+/// layout at `depth`, relative to Porter-Thomas
+pub fn relative_collision(template: &RcsConfig, depth: usize, n_instances: usize) -> f64 {
+    let config = RcsConfig { depth, n_instances, ..template.clone() };
+    let total: f64 = (0..n_instances)
+        .map(|i| {
+            let instance = config.instance(i);
+            let mut sim = QuantumSimulator::with_seeds(instance.n_qubits, instance.circuit_seed, instance.sampling_seed);
+            sim.set_threads(instance.threads);
+            sim.apply_circuit(&generate_rcs_circuit(&instance));
+            let report = PorterThomasReport::new(&sim.probabilities());
+            report.collision / report.porter_thomas_collision
+        })
+        .sum();
+    total / n_instances as f64
+}
+
+/// Smallest depth up to `max_depth` at which circuits with `template`'s
+/// qubits and entangler anticoncentrate: the mean collision probability of
+/// `n_instances` circuits is within `tolerance` of Porter-Thomas
+///
+/// Simulates ideal state vectors, so `template` must fit the state-vector
+/// backend.
+pub fn anticoncentration_depth(
+    template: &RcsConfig,
+    max_depth: usize,
+    n_instances: usize,
+    tolerance: f64,
+) -> Option<usize> {
+    (1..=max_depth).find(|&depth| relative_collision(template, depth, n_instances) <= 1.0 + tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_exponential_weights_pass() {
+        // Normalized Exp(1) weights are exactly Porter-Thomas distributed
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let weights: Vec<f64> = (0..4096).map(|_| -(1.0 - rng.gen::<f64>()).ln()).collect();
+        let total: f64 = weights.iter().sum();
+        let probs: Vec<f64> = weights.iter().map(|w| w / total).collect();
+        let report = PorterThomasReport::new(&probs);
+        assert!(report.anticoncentrated(0.1), "{:?}", report);
+        assert!(report.ks_p_value > 0.01, "{:?}", report);
+        assert_eq!(report.histogram.iter().sum::<usize>(), 4096);
+        assert!((report.expected.iter().sum::<f64>() - 4096.0).abs() < 1e-9);
+
+        // A uniform distribution has collision 1 and is far from Exp(1)
+        let uniform = PorterThomasReport::new(&[1.0 / 64.0; 64]);
+        assert!((uniform.collision - 1.0).abs() < 1e-12);
+        assert!(uniform.ks_statistic > 0.5 && uniform.ks_p_value < 1e-6);
+    }
+
+    #[test]
+    fn test_depth_reaches_anticoncentration() {
+        let template = RcsConfig::seeded(1, 8, 100, 4);
+        // A single cycle leaves most qubits in product states
+        assert!(relative_collision(&template, 1, 3) > 1.5);
+        let depth = anticoncentration_depth(&template, 30, 3, 0.1).unwrap();
+        assert!(depth > 1);
+        assert!(relative_collision(&template, depth, 3) <= 1.1);
+    }
+}
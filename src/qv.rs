@@ -0,0 +1,198 @@
+//! Quantum volume and heavy output generation (HOG)
+//!
+//! The protocol of Cross et al. (2019): each of `depth` layers pairs the
+//! qubits along a random permutation and applies a Haar-random SU(4) gate to
+//! every pair. An outcome is heavy if its ideal probability exceeds the
+//! median of the ideal distribution; ideal sampling of Porter-Thomas outputs
+//! hits heavy outcomes with probability (1 + ln 2)/2 ≈ 0.85, uniform noise
+//! with 1/2. A device achieves quantum volume 2ⁿ on square (depth = n)
+//! circuits if the mean heavy-output probability over circuits exceeds 2/3
+//! by two binomial standard errors.
+
+use crate::{collect_samples, BackendKind, Circuit, Gate, Moment, NoiseModel, QuantumSimulator, RcsConfig};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Heavy-output probability a device has to exceed
+pub const HOG_THRESHOLD: f64 = 2.0 / 3.0;
+
+/// Standard errors the mean has to clear the threshold by (≈97.7% one-sided)
+pub const HOG_CONFIDENCE_Z: f64 = 2.0;
+
+/// Heavy outputs of one model circuit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CircuitHeavyOutput {
+    pub circuit_seed: u64,
+    pub sampling_seed: u64,
+    /// Fraction of the shots that were heavy
+    pub heavy_output_probability: f64,
+    /// Heavy-output probability of ideal sampling, Σ p over heavy outcomes
+    pub ideal_heavy_output_probability: f64,
+    /// Linear XEB of the same shots
+    pub xeb_score: f64,
+}
+
+/// Result of a quantum volume run, the counterpart of [`crate::RcsResult`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QvResult {
+    pub date: String,
+    pub qubits: usize,
+    /// SU(4) layers; quantum volume is only defined for depth = qubits
+    pub depth: usize,
+    pub circuits: usize,
+    /// Shots per circuit
+    pub samples: usize,
+    pub runtime_ms: u64,
+    /// Seeds of the first circuit; the others derive theirs from them
+    pub circuit_seed: u64,
+    pub sampling_seed: u64,
+    /// Mean heavy-output probability over circuits
+    pub heavy_output_probability: f64,
+    /// Binomial standard error √(h(1-h)/circuits)
+    pub heavy_output_std_error: f64,
+    /// Heavy-output probability minus [`HOG_CONFIDENCE_Z`] standard errors
+    pub heavy_output_lower_bound: f64,
+    /// Mean heavy-output probability of ideal sampling
+    pub ideal_heavy_output_probability: f64,
+    /// Mean linear XEB of the same shots, for comparison with RCS runs
+    pub xeb_score: f64,
+    /// Whether the lower bound clears [`HOG_THRESHOLD`]
+    pub passed: bool,
+    /// 2ⁿ for a passing square run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantum_volume: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise: Option<NoiseModel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trajectories: Option<usize>,
+    pub instances: Vec<CircuitHeavyOutput>,
+}
+
+/// Generate the quantum volume model circuit of `config` from its circuit
+/// seed: `config.depth` layers of random SU(4) gates on random pairings
+pub fn generate_qv_circuit(config: &RcsConfig) -> Circuit {
+    let n_qubits = config.n_qubits;
+    let mut circuit = Circuit::new(n_qubits);
+    let mut rng = ChaCha8Rng::seed_from_u64(config.circuit_seed);
+    let mut qubits: Vec<usize> = (0..n_qubits).collect();
+    for _ in 0..config.depth {
+        // An odd qubit out idles for the layer
+        qubits.shuffle(&mut rng);
+        let mut layer = Moment::new();
+        for pair in qubits.chunks_exact(2) {
+            layer.push(Gate::RandomSU4(rng.gen()), vec![pair[0], pair[1]]);
+        }
+        circuit.push_moment(layer);
+    }
+    circuit
+}
+
+/// Run the quantum volume protocol with `config.n_instances` model circuits
+/// of `config.n_samples` shots each
+///
+/// Circuits are simulated on the state vector whatever `config.backend`
+/// says; noise is sampled as in RCS runs, by trajectories, white noise and
+/// readout flips.
+pub fn run_quantum_volume(config: &RcsConfig) -> QvResult {
+    let start = std::time::Instant::now();
+    let config = RcsConfig { backend: BackendKind::StateVector, ..config.clone() };
+    let run = |index: usize| run_circuit(&config.instance(index));
+    let instances: Vec<CircuitHeavyOutput> = if config.parallel_instances {
+        (0..config.n_instances).into_par_iter().map(run).collect()
+    } else {
+        (0..config.n_instances).map(run).collect()
+    };
+
+    let n = instances.len() as f64;
+    let mean = |f: fn(&CircuitHeavyOutput) -> f64| instances.iter().map(f).sum::<f64>() / n;
+    let heavy = mean(|c| c.heavy_output_probability);
+    let std_error = (heavy * (1.0 - heavy) / n).sqrt();
+    let lower_bound = heavy - HOG_CONFIDENCE_Z * std_error;
+    let passed = lower_bound > HOG_THRESHOLD;
+    let noise = &config.noise;
+
+    QvResult {
+        date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        qubits: config.n_qubits,
+        depth: config.depth,
+        circuits: config.n_instances,
+        samples: config.n_samples,
+        runtime_ms: start.elapsed().as_millis() as u64,
+        circuit_seed: config.circuit_seed,
+        sampling_seed: config.sampling_seed,
+        heavy_output_probability: heavy,
+        heavy_output_std_error: std_error,
+        heavy_output_lower_bound: lower_bound,
+        ideal_heavy_output_probability: mean(|c| c.ideal_heavy_output_probability),
+        xeb_score: mean(|c| c.xeb_score),
+        passed,
+        quantum_volume: (passed && config.depth == config.n_qubits).then(|| 1u64 << config.n_qubits),
+        noise: (!noise.is_noiseless()).then(|| noise.clone()),
+        noise_sha256: config.noise_sha256.clone(),
+        trajectories: noise.is_stochastic().then_some(config.trajectories),
+        instances,
+    }
+}
+
+/// Sample one model circuit and count its heavy outputs
+fn run_circuit(config: &RcsConfig) -> CircuitHeavyOutput {
+    let circuit = generate_qv_circuit(config);
+    let mut sim = QuantumSimulator::with_seeds(config.n_qubits, config.circuit_seed, config.sampling_seed);
+    sim.set_threads(config.threads);
+    sim.apply_circuit(&circuit);
+    let (samples, _) = collect_samples(config, &mut sim, &circuit, &circuit);
+
+    let probs = sim.probabilities();
+    let mut sorted = probs.clone();
+    sorted.sort_by(f64::total_cmp);
+    // 2ⁿ outcomes, so the median sits between the two middle values
+    let half = sorted.len() / 2;
+    let median = (sorted[half - 1] + sorted[half]) / 2.0;
+    let heavy = samples.iter().filter(|&&s| probs[s as usize] > median).count();
+    let n_shots = samples.len().max(1) as f64;
+    let dim = probs.len() as f64;
+    CircuitHeavyOutput {
+        circuit_seed: config.circuit_seed,
+        sampling_seed: config.sampling_seed,
+        heavy_output_probability: heavy as f64 / n_shots,
+        ideal_heavy_output_probability: probs.iter().filter(|&&p| p > median).sum(),
+        xeb_score: dim * samples.iter().map(|&s| probs[s as usize]).sum::<f64>() / n_shots - 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::WhiteNoise;
+
+    #[test]
+    fn test_ideal_device_passes() {
+        let config = RcsConfig { n_instances: 20, ..RcsConfig::seeded(5, 5, 200, 3) };
+        let circuit = generate_qv_circuit(&config);
+        assert_eq!(circuit.moments.len(), 5);
+        assert!(circuit.operations().all(|op| matches!(op.gate, Gate::RandomSU4(_)) && op.qubits.len() == 2));
+
+        let result = run_quantum_volume(&config);
+        assert_eq!(result.instances.len(), 20);
+        // Close to the Porter-Thomas value (1 + ln 2)/2
+        assert!((result.ideal_heavy_output_probability - 0.85).abs() < 0.05, "{:?}", result);
+        assert!(result.passed && result.quantum_volume == Some(32));
+        let parallel = RcsConfig { parallel_instances: true, ..config };
+        assert_eq!(run_quantum_volume(&parallel).instances, result.instances);
+    }
+
+    #[test]
+    fn test_noisy_device_fails() {
+        // Heavy-output probability falls to F·h_ideal + (1 - F)/2
+        let noise = NoiseModel { white_noise: Some(WhiteNoise::Fidelity(0.2)), ..NoiseModel::default() };
+        let config = RcsConfig { noise, n_instances: 20, ..RcsConfig::seeded(4, 4, 200, 8) };
+        let result = run_quantum_volume(&config);
+        let expected = 0.2 * result.ideal_heavy_output_probability + 0.4;
+        assert!((result.heavy_output_probability - expected).abs() < 0.05, "{:?}", result);
+        assert!(!result.passed && result.quantum_volume.is_none());
+    }
+}
@@ -0,0 +1,76 @@
+//! Batched sampling from a probability distribution
+//!
+//! The cumulative distribution is built once in O(2ⁿ); each shot is then
+//! an O(n) binary search instead of a full linear scan.
+
+use rand::Rng;
+use std::collections::BTreeMap;
+
+/// Cumulative distribution over basis states, reusable across shots
+#[derive(Debug, Clone)]
+pub struct CdfSampler {
+    cdf: Vec<f64>,
+}
+
+impl CdfSampler {
+    /// Build the cumulative distribution of `probs`
+    ///
+    /// `probs` need not be exactly normalized; draws are scaled to its total.
+    pub fn new(probs: &[f64]) -> Self {
+        let mut total = 0.0;
+        let cdf = probs
+            .iter()
+            .map(|p| {
+                total += p;
+                total
+            })
+            .collect();
+        Self { cdf }
+    }
+
+    /// Draw one outcome
+    pub fn sample_one<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let total = self.cdf.last().copied().unwrap_or(0.0);
+        let r = rng.gen::<f64>() * total;
+        // First index whose cumulative probability exceeds r
+        self.cdf.partition_point(|&c| c <= r).min(self.cdf.len() - 1)
+    }
+
+    /// Draw `n_shots` independent outcomes
+    pub fn sample<R: Rng + ?Sized>(&self, n_shots: usize, rng: &mut R) -> Vec<usize> {
+        (0..n_shots).map(|_| self.sample_one(rng)).collect()
+    }
+}
+
+/// Count how often each outcome occurs
+pub fn histogram(samples: &[usize]) -> BTreeMap<usize, usize> {
+    let mut counts = BTreeMap::new();
+    for &s in samples {
+        *counts.entry(s).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_zero_probability_never_sampled() {
+        let sampler = CdfSampler::new(&[0.0, 0.5, 0.0, 0.5, 0.0]);
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let counts = histogram(&sampler.sample(4000, &mut rng));
+        assert_eq!(counts.keys().copied().collect::<Vec<_>>(), vec![1, 3]);
+        assert!((counts[&1] as f64 / 4000.0 - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_unnormalized_input() {
+        let sampler = CdfSampler::new(&[1.0, 3.0]);
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let counts = histogram(&sampler.sample(4000, &mut rng));
+        assert!((counts[&1] as f64 / 4000.0 - 0.75).abs() < 0.05);
+    }
+}
@@ -0,0 +1,199 @@
+//! Scoring externally measured bitstrings
+//!
+//! Samples from real hardware are scored like simulated ones: the ideal
+//! distribution of the circuit comes from [`QuantumSimulator`] and every
+//! estimator of [`crate::xeb`] is evaluated on the measured outcomes.
+//!
+//! Bitstrings are written with qubit 0 first, so `011` has qubits 1 and 2
+//! in |1⟩; `reverse_bits` reads them with qubit 0 last, as Qiskit prints
+//! counts. A sample file is either text with one bitstring per line,
+//! optionally followed by a count, or a JSON object of counts by bitstring.
+//! Blank lines and lines starting with `#` are skipped.
+
+use crate::{stats, BackendKind, Circuit, Estimate, Estimator, QuantumSimulator};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Estimates of a set of measured samples
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreResult {
+    pub date: String,
+    pub qubits: usize,
+    /// Number of moments of the scored circuit
+    pub moments: usize,
+    /// Total number of shots
+    pub samples: u64,
+    /// Seed the circuit was generated from, if it was not read from a file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_seed: Option<u64>,
+    /// Every estimator defined for the circuit, by name
+    pub estimates: BTreeMap<String, Estimate>,
+    /// 95% percentile bootstrap intervals, by estimator name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub bootstrap_ci: BTreeMap<String, [f64; 2]>,
+    /// Linear XEB of ideal sampling, 2ⁿ·Σp² - 1, what a perfect device scores
+    pub ideal_xeb: f64,
+}
+
+/// Score the measured `(outcome, count)` pairs of `circuit` with every
+/// estimator
+///
+/// Shots are weighted by their counts rather than expanded, so histograms of
+/// any size score in memory proportional to their distinct outcomes.
+/// `bootstrap` is the number of resamples of each interval, drawn from
+/// `seed`. Fails if the circuit is invalid or too large to simulate, or if
+/// an outcome does not fit its qubits.
+pub fn score_samples(
+    circuit: &Circuit,
+    counts: &[(u128, u64)],
+    threads: usize,
+    bootstrap: Option<usize>,
+    seed: u64,
+) -> Result<ScoreResult, String> {
+    let n_qubits = circuit.n_qubits;
+    circuit.validate()?;
+    let max_qubits = BackendKind::StateVector.max_qubits();
+    if !(1..=max_qubits).contains(&n_qubits) {
+        return Err(format!("scoring is limited to {} qubits, the circuit has {}", max_qubits, n_qubits));
+    }
+    if let Some((outcome, _)) = counts.iter().find(|(s, _)| s >> n_qubits != 0) {
+        return Err(format!("outcome {:#b} out of range for {} qubits", outcome, n_qubits));
+    }
+    let total = counts
+        .iter()
+        .try_fold(0u64, |total, &(_, count)| total.checked_add(count))
+        .ok_or("total count exceeds 2⁶⁴ shots")?;
+    let mut sim = QuantumSimulator::with_seeds(n_qubits, seed, seed);
+    sim.set_threads(threads);
+    sim.apply_circuit(circuit);
+    let ideal = sim.probabilities();
+    let sample_probs: Vec<f64> = counts.iter().map(|&(s, _)| ideal[s as usize]).collect();
+    let shot_counts: Vec<u64> = counts.iter().map(|&(_, count)| count).collect();
+
+    let mut estimates = BTreeMap::new();
+    let mut bootstrap_ci = BTreeMap::new();
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    for estimator in Estimator::ALL {
+        let Some(terms) = estimator.terms(&sample_probs, n_qubits, Some(&ideal)) else { continue };
+        let terms = terms.counted(shot_counts.clone());
+        estimates.insert(estimator.name().to_string(), terms.estimate());
+        if let Some(n_resamples) = bootstrap.filter(|_| total > 0) {
+            let ci = terms.bootstrap_ci(n_resamples, stats::BOOTSTRAP_CONFIDENCE, &mut rng);
+            bootstrap_ci.insert(estimator.name().to_string(), ci);
+        }
+    }
+
+    Ok(ScoreResult {
+        date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        qubits: n_qubits,
+        moments: circuit.moments.len(),
+        samples: total,
+        circuit_seed: None,
+        estimates,
+        bootstrap_ci,
+        ideal_xeb: ideal.len() as f64 * ideal.iter().map(|p| p * p).sum::<f64>() - 1.0,
+    })
+}
+
+/// Parse a sample file, a JSON object of counts if `json` and text lines
+/// otherwise, into the count of each distinct outcome, in outcome order
+pub fn parse_samples(
+    contents: &str,
+    json: bool,
+    n_qubits: usize,
+    reverse_bits: bool,
+) -> Result<Vec<(u128, u64)>, String> {
+    let counts: Vec<(String, u64)> = if json {
+        let map: BTreeMap<String, u64> =
+            serde_json::from_str(contents).map_err(|e| format!("invalid counts JSON: {}", e))?;
+        map.into_iter().collect()
+    } else {
+        contents
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(number, line)| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [bits] => Ok((bits.to_string(), 1)),
+                [bits, count] => count
+                    .parse()
+                    .map(|count| (bits.to_string(), count))
+                    .map_err(|_| format!("line {}: invalid count {:?}", number, count)),
+                _ => Err(format!("line {}: expected a bitstring and an optional count", number)),
+            })
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut histogram = BTreeMap::new();
+    for (bits, count) in counts {
+        let outcome = parse_bitstring(&bits, n_qubits, reverse_bits)?;
+        let total: &mut u64 = histogram.entry(outcome).or_default();
+        *total = total.checked_add(count).ok_or_else(|| format!("count of {:?} exceeds 2⁶⁴", bits))?;
+    }
+    Ok(histogram.into_iter().collect())
+}
+
+/// Outcome of a bitstring of `n_qubits` 0s and 1s
+fn parse_bitstring(bits: &str, n_qubits: usize, reverse_bits: bool) -> Result<u128, String> {
+    if bits.len() != n_qubits {
+        return Err(format!("bitstring {:?} does not have {} bits", bits, n_qubits));
+    }
+    bits.bytes().enumerate().try_fold(0u128, |outcome, (i, bit)| {
+        let q = if reverse_bits { n_qubits - 1 - i } else { i };
+        match bit {
+            b'0' => Ok(outcome),
+            b'1' => Ok(outcome | 1 << q),
+            _ => Err(format!("bitstring {:?} is not made of 0s and 1s", bits)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_rcs_circuit, Backend, RcsConfig};
+
+    #[test]
+    fn test_parse_samples() {
+        let text = "# shots\n011\n\n110 2\n";
+        assert_eq!(parse_samples(text, false, 3, false).unwrap(), vec![(0b011, 2), (0b110, 1)]);
+        assert_eq!(parse_samples(text, false, 3, true).unwrap(), vec![(0b011, 1), (0b110, 2)]);
+        let json = r#"{"100": 1, "001": 2}"#;
+        assert_eq!(parse_samples(json, true, 3, false).unwrap(), vec![(0b001, 1), (0b100, 2)]);
+        assert_eq!(parse_samples("01\n01 4\n", false, 2, false).unwrap(), vec![(0b10, 5)]);
+        assert!(parse_samples("01\n", false, 3, false).is_err());
+        assert!(parse_samples("012\n", false, 3, false).is_err());
+        assert!(parse_samples("011 x\n", false, 3, false).is_err());
+    }
+
+    #[test]
+    fn test_scores_simulated_samples() {
+        // Scoring a run's own samples reproduces its estimates
+        let config = RcsConfig::seeded(8, 8, 2000, 12);
+        let circuit = generate_rcs_circuit(&config);
+        let mut sim = QuantumSimulator::with_seeds(8, config.circuit_seed, config.sampling_seed);
+        sim.apply_circuit(&circuit);
+        let mut counts = BTreeMap::new();
+        for outcome in Backend::sample(&mut sim, config.n_samples) {
+            *counts.entry(outcome).or_default() += 1;
+        }
+        let counts: Vec<(u128, u64)> = counts.into_iter().collect();
+        let result = score_samples(&circuit, &counts, 0, Some(100), 1).unwrap();
+        let run = crate::run_benchmark_with_config(&config);
+        assert_eq!(result.samples, 2000);
+        for (name, estimate) in &run.estimates {
+            assert!((result.estimates[name].value - estimate.value).abs() < 1e-9, "{}", name);
+        }
+        let [lo, hi] = result.bootstrap_ci["normalized"];
+        assert!(lo < result.estimates["normalized"].value && result.estimates["normalized"].value < hi);
+
+        // Outcomes beyond the circuit's qubits are reported, not scored
+        assert!(score_samples(&circuit, &[(1 << 8, 1)], 0, None, 1).is_err());
+
+        // A hardware-sized histogram scores without expanding its shots
+        let huge = score_samples(&circuit, &[(0b101, 1_000_000_000_000), (0b11, 1)], 0, Some(100), 1).unwrap();
+        assert_eq!(huge.samples, 1_000_000_000_001);
+    }
+}
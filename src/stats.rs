@@ -0,0 +1,273 @@
+//! Statistical uncertainty of benchmark scores
+//!
+//! XEB estimators are sample means of a per-shot quantity (for linear XEB,
+//! 2ⁿ·p(x) of each measured bitstring), so their standard error follows from
+//! the sample variance. The percentile bootstrap resamples the same per-shot
+//! values and needs no normality assumption, which matters for few shots.
+//! Shots drawn from one noisy trajectory share its errors, so for those the
+//! error and the bootstrap work on whole trajectories instead of shots.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Confidence level of bootstrap intervals
+pub const BOOTSTRAP_CONFIDENCE: f64 = 0.95;
+
+/// Location and spread of a set of scores
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub mean: f64,
+    pub median: f64,
+    /// Sample standard deviation, 0 for a single value
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Summary {
+    /// Summarize a non-empty set of values
+    pub fn of(values: &[f64]) -> Self {
+        assert!(!values.is_empty(), "cannot summarize no values");
+        let n = values.len();
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let median = match n % 2 {
+            1 => sorted[n / 2],
+            _ => (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0,
+        };
+        let mean = values.iter().sum::<f64>() / n as f64;
+        let std_dev = mean_with_error(values).map_or(0.0, |(_, error)| error * (n as f64).sqrt());
+        Self { mean, median, std_dev, min: sorted[0], max: sorted[n - 1] }
+    }
+}
+
+/// Sample mean of `values` and its standard error √(s²/N), `None` for
+/// fewer than two values
+pub fn mean_with_error(values: &[f64]) -> Option<(f64, f64)> {
+    let n = values.len();
+    if n < 2 {
+        return None;
+    }
+    let mean = values.iter().sum::<f64>() / n as f64;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+    Some((mean, (variance / n as f64).sqrt()))
+}
+
+/// Sample mean of `values` and its cluster-robust standard error, where
+/// `clusters` are the sizes of consecutive groups of correlated values;
+/// `None` for fewer than two clusters
+///
+/// With clusters of one value this is [`mean_with_error`].
+pub fn clustered_mean_with_error(values: &[f64], clusters: &[usize]) -> Option<(f64, f64)> {
+    assert_eq!(clusters.iter().sum::<usize>(), values.len(), "clusters do not cover the values");
+    let c = clusters.len();
+    if c < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    // C/(C-1)·Σ(S_c - n_c·m)²/N² over the cluster sums S_c
+    let residuals: f64 = cluster_sums(values, clusters).map(|(size, sum)| (sum - size as f64 * mean).powi(2)).sum();
+    Some((mean, (c as f64 / (c - 1) as f64 * residuals).sqrt() / n))
+}
+
+/// Percentile bootstrap interval of the mean of `values` at `confidence`,
+/// from `n_resamples` resamples drawn with replacement
+pub fn bootstrap_mean_ci<R: Rng + ?Sized>(values: &[f64], n_resamples: usize, confidence: f64, rng: &mut R) -> [f64; 2] {
+    assert!(!values.is_empty() && n_resamples > 0, "bootstrap needs values and resamples");
+    let n = values.len();
+    let means = (0..n_resamples)
+        .map(|_| (0..n).map(|_| values[rng.gen_range(0..n)]).sum::<f64>() / n as f64)
+        .collect();
+    percentile_interval(means, confidence)
+}
+
+/// Mean of `values` weighted by the integer `counts` of each value and its
+/// standard error, as if every value had been repeated `count` times;
+/// `None` for fewer than two counted values
+pub fn weighted_mean_with_error(values: &[f64], counts: &[u64]) -> Option<(f64, f64)> {
+    assert_eq!(values.len(), counts.len(), "one count per value");
+    let n = counts.iter().sum::<u64>() as f64;
+    if n < 2.0 {
+        return None;
+    }
+    let mean = values.iter().zip(counts).map(|(v, &c)| v * c as f64).sum::<f64>() / n;
+    let variance = values.iter().zip(counts).map(|(v, &c)| c as f64 * (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    Some((mean, (variance / n).sqrt()))
+}
+
+/// Percentile bootstrap interval of the count-weighted mean of `values`
+///
+/// Each resample redistributes all counted shots multinomially over the
+/// values, drawn value by value from conditional binomials, so the cost
+/// does not grow with the counts.
+pub fn bootstrap_weighted_mean_ci<R: Rng + ?Sized>(
+    values: &[f64],
+    counts: &[u64],
+    n_resamples: usize,
+    confidence: f64,
+    rng: &mut R,
+) -> [f64; 2] {
+    assert_eq!(values.len(), counts.len(), "one count per value");
+    let total: u64 = counts.iter().sum();
+    assert!(total > 0 && n_resamples > 0, "bootstrap needs values and resamples");
+    let means = (0..n_resamples)
+        .map(|_| {
+            let (mut shots_left, mut counts_left) = (total, total);
+            let mut sum = 0.0;
+            for (&value, &count) in values.iter().zip(counts) {
+                if shots_left == 0 {
+                    break;
+                }
+                let k = binomial(shots_left, count as f64 / counts_left as f64, rng);
+                sum += k as f64 * value;
+                shots_left -= k;
+                counts_left -= count;
+            }
+            sum / total as f64
+        })
+        .collect();
+    percentile_interval(means, confidence)
+}
+
+/// Draw from Binomial(n, p), by inversion for a small mean and from the
+/// normal approximation otherwise
+fn binomial<R: Rng + ?Sized>(n: u64, p: f64, rng: &mut R) -> u64 {
+    if n == 0 || p <= 0.0 {
+        return 0;
+    }
+    if p >= 1.0 {
+        return n;
+    }
+    if p > 0.5 {
+        return n - binomial(n, 1.0 - p, rng);
+    }
+    let mean = n as f64 * p;
+    if mean < 30.0 {
+        // Walk up the CDF from P(0) = (1-p)ⁿ ≥ e⁻⁶⁰
+        let u: f64 = rng.gen();
+        let ratio = p / (1.0 - p);
+        let mut pmf = (1.0 - p).powf(n as f64);
+        let (mut k, mut cdf) = (0, pmf);
+        while cdf < u && k < n {
+            pmf *= ratio * (n - k) as f64 / (k + 1) as f64;
+            k += 1;
+            cdf += pmf;
+        }
+        k
+    } else {
+        // Box-Muller
+        let (u1, u2): (f64, f64) = (rng.gen(), rng.gen());
+        let z = (-2.0 * (1.0 - u1).ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+        (mean + (mean * (1.0 - p)).sqrt() * z).round().clamp(0.0, n as f64) as u64
+    }
+}
+
+/// Percentile bootstrap interval of the mean of `values` that resamples the
+/// consecutive clusters of sizes `clusters` instead of single values
+pub fn bootstrap_clustered_mean_ci<R: Rng + ?Sized>(
+    values: &[f64],
+    clusters: &[usize],
+    n_resamples: usize,
+    confidence: f64,
+    rng: &mut R,
+) -> [f64; 2] {
+    assert!(!values.is_empty() && n_resamples > 0, "bootstrap needs values and resamples");
+    assert_eq!(clusters.iter().sum::<usize>(), values.len(), "clusters do not cover the values");
+    let sums: Vec<(usize, f64)> = cluster_sums(values, clusters).collect();
+    let c = sums.len();
+    let means = (0..n_resamples)
+        .map(|_| {
+            let (size, sum) = (0..c).map(|_| sums[rng.gen_range(0..c)]).fold((0, 0.0), |(n, s), (m, t)| (n + m, s + t));
+            sum / size.max(1) as f64
+        })
+        .collect();
+    percentile_interval(means, confidence)
+}
+
+/// Size and sum of each consecutive cluster of `values`
+fn cluster_sums<'a>(values: &'a [f64], clusters: &'a [usize]) -> impl Iterator<Item = (usize, f64)> + 'a {
+    clusters.iter().scan(0, move |start, &size| {
+        let sum = values[*start..*start + size].iter().sum();
+        *start += size;
+        Some((size, sum))
+    })
+}
+
+/// Central interval at `confidence` of the bootstrap means
+fn percentile_interval(mut means: Vec<f64>, confidence: f64) -> [f64; 2] {
+    means.sort_by(f64::total_cmp);
+    let n_resamples = means.len();
+    let tail = (1.0 - confidence) / 2.0;
+    let quantile = |q: f64| means[((q * n_resamples as f64) as usize).min(n_resamples - 1)];
+    [quantile(tail), quantile(1.0 - tail)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_mean_with_error() {
+        let (mean, error) = mean_with_error(&[1.0, 2.0, 3.0, 4.0]).unwrap();
+        assert_eq!(mean, 2.5);
+        // s² = 5/3
+        assert!((error - (5.0f64 / 3.0 / 4.0).sqrt()).abs() < 1e-12);
+        assert!(mean_with_error(&[1.0]).is_none());
+    }
+
+    #[test]
+    fn test_clustered_error() {
+        let values = [1.0, 2.0, 3.0, 4.0];
+        let (mean, error) = clustered_mean_with_error(&values, &[1; 4]).unwrap();
+        assert_eq!((mean, error), mean_with_error(&values).unwrap());
+        // Identical values within a cluster carry no more information than one
+        let (_, paired) = clustered_mean_with_error(&[1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0], &[2; 4]).unwrap();
+        assert!((paired - error).abs() < 1e-12);
+        assert!(clustered_mean_with_error(&values, &[4]).is_none());
+
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let [lo, hi] = bootstrap_clustered_mean_ci(&values, &[2, 2], 200, BOOTSTRAP_CONFIDENCE, &mut rng);
+        // Only the cluster means 1.5 and 3.5 can be resampled
+        assert!(lo >= 1.5 && hi <= 3.5 && lo < hi);
+    }
+
+    #[test]
+    fn test_weighted_matches_repeated_values() {
+        let (mean, error) = weighted_mean_with_error(&[1.0, 2.0, 4.0], &[3, 1, 2]).unwrap();
+        let repeated = mean_with_error(&[1.0, 1.0, 1.0, 2.0, 4.0, 4.0]).unwrap();
+        assert!((mean - repeated.0).abs() < 1e-12 && (error - repeated.1).abs() < 1e-12);
+        assert!(weighted_mean_with_error(&[1.0, 2.0], &[1, 0]).is_none());
+
+        // Huge counts cost no more than small ones
+        let counts = [3_000_000_000_000, 1_000_000_000_000];
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let [lo, hi] = bootstrap_weighted_mean_ci(&[0.0, 1.0], &counts, 200, BOOTSTRAP_CONFIDENCE, &mut rng);
+        assert!(lo < 0.25 && 0.25 < hi && hi - lo < 1e-5, "[{}, {}]", lo, hi);
+        let [lo, hi] = bootstrap_weighted_mean_ci(&[0.0, 1.0], &[30, 10], 1000, BOOTSTRAP_CONFIDENCE, &mut rng);
+        assert!(lo < 0.25 && 0.25 < hi);
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::of(&[4.0, 1.0, 3.0, 2.0]);
+        assert_eq!((summary.mean, summary.median, summary.min, summary.max), (2.5, 2.5, 1.0, 4.0));
+        assert!((summary.std_dev - (5.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(Summary::of(&[7.0]).std_dev, 0.0);
+        assert_eq!(Summary::of(&[3.0, 1.0, 2.0]).median, 2.0);
+    }
+
+    #[test]
+    fn test_bootstrap_matches_standard_error() {
+        // Exponential values, as 2ⁿ·p(x) under Porter-Thomas
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let values: Vec<f64> = (0..1000).map(|_| -(1.0 - rng.gen::<f64>()).ln()).collect();
+        let (mean, error) = mean_with_error(&values).unwrap();
+        let [lo, hi] = bootstrap_mean_ci(&values, 1000, BOOTSTRAP_CONFIDENCE, &mut rng);
+        assert!(lo < mean && mean < hi);
+        // A 95% interval spans about ±1.96 standard errors
+        assert!(((hi - lo) / (2.0 * 1.96 * error) - 1.0).abs() < 0.2, "{} vs {}", hi - lo, error);
+    }
+}
@@ -0,0 +1,213 @@
+//! Fidelity-vs-depth sweeps
+//!
+//! Under a digital error model every cycle multiplies the fidelity by the
+//! same factor, so XEB(d) = A·f^d. Fitting ln XEB linearly in d, weighted by
+//! the relative standard errors of the points, gives the per-cycle fidelity
+//! f as one depth-independent number. Points with a non-positive score carry
+//! no usable logarithm and are left out of the fit.
+
+use crate::{derive_seed, run_with_anticoncentration_depth, search_anticoncentration_depth, RcsConfig};
+use serde::{Deserialize, Serialize};
+
+/// First seed stream of sweep depths, clear of the circuit instance streams
+const SWEEP_STREAM: u64 = 2 << 32;
+
+/// Score of one depth of a sweep
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthPoint {
+    pub depth: usize,
+    pub xeb_score: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xeb_std_error: Option<f64>,
+    pub circuit_seed: u64,
+    pub sampling_seed: u64,
+    /// Digital error model prediction of the circuit fidelity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub predicted_fidelity: Option<f64>,
+}
+
+/// Fit of XEB(d) = A·f^d with standard errors of its parameters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DecayFit {
+    /// Amplitude A, which absorbs depth-independent errors such as readout
+    pub amplitude: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amplitude_error: Option<f64>,
+    /// Per-cycle fidelity f
+    pub fidelity: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fidelity_error: Option<f64>,
+    /// χ² per degree of freedom of a weighted fit, `None` without spare points
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reduced_chi_squared: Option<f64>,
+    /// Number of points that entered the fit
+    pub n_points: usize,
+}
+
+/// Result of a depth sweep at fixed qubit count
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepthSweep {
+    pub date: String,
+    pub qubits: usize,
+    pub samples: usize,
+    pub estimator: String,
+    /// Seeds the per-depth seeds are derived from
+    pub circuit_seed: u64,
+    pub sampling_seed: u64,
+    pub points: Vec<DepthPoint>,
+    /// `None` if fewer than two depths scored above zero
+    pub fit: Option<DecayFit>,
+    /// Depth at which the layout anticoncentrates, searched once for the
+    /// sweep if the Porter-Thomas diagnostics were requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anticoncentration_depth: Option<usize>,
+    pub runtime_ms: u64,
+}
+
+/// Configuration of the sweep point at `depth`, with its own circuit
+///
+/// Circuits of one seed at different depths share their first cycles, so
+/// every depth derives fresh seeds to keep the points independent.
+pub fn depth_config(base: &RcsConfig, depth: usize) -> RcsConfig {
+    let stream = SWEEP_STREAM + depth as u64;
+    RcsConfig {
+        depth,
+        circuit_seed: derive_seed(base.circuit_seed, stream),
+        sampling_seed: derive_seed(base.sampling_seed, stream),
+        ..base.clone()
+    }
+}
+
+/// Run `base` at each of `depths` and fit the XEB decay, failing if the
+/// selected estimator is undefined at some depth
+pub fn run_depth_sweep(base: &RcsConfig, depths: &[usize]) -> Result<DepthSweep, String> {
+    let start = std::time::Instant::now();
+    // The search depends only on the qubits and entangler, shared by all depths
+    let anticoncentration_depth = search_anticoncentration_depth(base);
+    let points: Vec<DepthPoint> = depths
+        .iter()
+        .map(|&depth| {
+            let result = run_with_anticoncentration_depth(&depth_config(base, depth), anticoncentration_depth)
+                .map_err(|e| format!("depth {}: {}", depth, e))?;
+            Ok(DepthPoint {
+                depth,
+                xeb_score: result.xeb_score,
+                xeb_std_error: result.xeb_std_error,
+                circuit_seed: result.circuit_seed.unwrap_or_default(),
+                sampling_seed: result.sampling_seed.unwrap_or_default(),
+                predicted_fidelity: result.predicted_fidelity,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(DepthSweep {
+        date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        qubits: base.n_qubits,
+        samples: base.n_samples,
+        estimator: base.estimator.name().to_string(),
+        circuit_seed: base.circuit_seed,
+        sampling_seed: base.sampling_seed,
+        fit: fit_decay(&points),
+        points,
+        anticoncentration_depth,
+        runtime_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+/// Weighted least-squares fit of ln XEB(d) = ln A + d·ln f
+///
+/// Points are weighted by (XEB/σ)² when every point has a standard error,
+/// and the parameter errors are then inflated by √χ²ᵣ if the scatter
+/// exceeds the error bars. Without standard errors the fit is unweighted and
+/// its errors come from the residuals alone.
+pub fn fit_decay(points: &[DepthPoint]) -> Option<DecayFit> {
+    let used: Vec<&DepthPoint> = points.iter().filter(|p| p.xeb_score > 0.0 && p.xeb_score.is_finite()).collect();
+    let weighted = used.iter().all(|p| p.xeb_std_error.is_some_and(|e| e > 0.0));
+    let data: Vec<(f64, f64, f64)> = used
+        .iter()
+        .map(|p| {
+            let weight = match p.xeb_std_error {
+                Some(error) if weighted => (p.xeb_score / error).powi(2),
+                _ => 1.0,
+            };
+            (p.depth as f64, p.xeb_score.ln(), weight)
+        })
+        .collect();
+
+    let sum = |f: &dyn Fn(f64, f64) -> f64| data.iter().map(|&(d, y, w)| w * f(d, y)).sum::<f64>();
+    let (s, sd, sy, sdd, sdy) = (sum(&|_, _| 1.0), sum(&|d, _| d), sum(&|_, y| y), sum(&|d, _| d * d), sum(&|d, y| d * y));
+    let det = s * sdd - sd * sd;
+    if data.len() < 2 || det <= 1e-12 * s * sdd {
+        return None;
+    }
+    let slope = (s * sdy - sd * sy) / det;
+    let intercept = (sdd * sy - sd * sdy) / det;
+
+    // (XᵀWX)⁻¹ scaled by the residual variance
+    let dof = data.len() - 2;
+    let chi_squared = sum(&|d, y| (y - intercept - slope * d).powi(2));
+    let reduced = (dof > 0).then(|| chi_squared / dof as f64);
+    let scale = match (weighted, reduced) {
+        (true, Some(r)) => Some(r.max(1.0)),
+        (true, None) => Some(1.0),
+        (false, r) => r,
+    };
+    let (amplitude, fidelity) = (intercept.exp(), slope.exp());
+    Some(DecayFit {
+        amplitude,
+        amplitude_error: scale.map(|c| amplitude * (c * sdd / det).sqrt()),
+        fidelity,
+        fidelity_error: scale.map(|c| fidelity * (c * s / det).sqrt()),
+        reduced_chi_squared: reduced.filter(|_| weighted),
+        n_points: data.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::{NoiseModel, WhiteNoise};
+
+    fn point(depth: usize, xeb_score: f64, xeb_std_error: Option<f64>) -> DepthPoint {
+        DepthPoint { depth, xeb_score, xeb_std_error, circuit_seed: 0, sampling_seed: 0, predicted_fidelity: None }
+    }
+
+    #[test]
+    fn test_fit_exact_decay() {
+        let points: Vec<DepthPoint> = (2..10).map(|d| point(d, 0.9 * 0.95f64.powi(d as i32), Some(0.01))).collect();
+        let fit = fit_decay(&points).unwrap();
+        assert!((fit.amplitude - 0.9).abs() < 1e-12 && (fit.fidelity - 0.95).abs() < 1e-12);
+        assert!(fit.reduced_chi_squared.unwrap() < 1e-20);
+        assert!(fit.fidelity_error.unwrap() > 0.0);
+
+        // Non-positive scores are skipped; two points determine the fit without errors
+        let fit = fit_decay(&[point(2, 0.5, None), point(4, 0.125, None), point(6, -0.01, None)]).unwrap();
+        assert!((fit.fidelity - 0.5).abs() < 1e-12 && fit.fidelity_error.is_none());
+        assert_eq!(fit.n_points, 2);
+        assert!(fit_decay(&[point(3, 0.5, None), point(3, 0.4, None)]).is_none());
+    }
+
+    #[test]
+    fn test_sweep_recovers_cycle_fidelity() {
+        let white_noise = WhiteNoise::GateFidelities { one_qubit: 0.995, two_qubit: 0.98, readout: 0.99 };
+        let noise = NoiseModel { white_noise: Some(white_noise), ..NoiseModel::default() };
+        let base = RcsConfig { noise, estimator: crate::Estimator::Normalized, ..RcsConfig::seeded(1, 6, 4000, 9) };
+        let sweep = run_depth_sweep(&base, &[2, 4, 6, 8, 10]).unwrap();
+        assert_eq!(sweep.points.len(), 5);
+        assert_ne!(sweep.points[0].circuit_seed, sweep.points[1].circuit_seed);
+
+        // The fit of the target fidelities themselves is the per-cycle fidelity to find
+        let targets: Vec<DepthPoint> =
+            sweep.points.iter().map(|p| point(p.depth, p.predicted_fidelity.unwrap(), None)).collect();
+        let target = fit_decay(&targets).unwrap().fidelity;
+        let fit = sweep.fit.unwrap();
+        assert!((fit.fidelity - target).abs() < 4.0 * fit.fidelity_error.unwrap() + 0.01, "{:?} vs {}", fit, target);
+        assert!(sweep.anticoncentration_depth.is_none());
+
+        // The anticoncentration depth is searched once, on the base layout
+        let base = RcsConfig { porter_thomas: true, ..RcsConfig::seeded(1, 4, 100, 9) };
+        let sweep = run_depth_sweep(&base, &[2, 4]).unwrap();
+        assert!(sweep.anticoncentration_depth.is_some());
+        assert_eq!(sweep.anticoncentration_depth, crate::search_anticoncentration_depth(&base));
+    }
+}
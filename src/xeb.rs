@@ -0,0 +1,249 @@
+//! Cross-entropy benchmarking estimators
+//!
+//! Every estimator is an affine function a·⟨t(x)⟩ + b of the mean of a
+//! per-shot term t over the measured bitstrings x, so they share the
+//! standard error and bootstrap of [`crate::stats`]:
+//!
+//! - linear XEB 2ⁿ·⟨p(x)⟩ - 1 (Arute et al. 2019)
+//! - normalized linear XEB (2ⁿ·⟨p(x)⟩ - 1)/(2ⁿ·Σp² - 1), which divides by
+//!   the exact score of ideal sampling instead of assuming Porter-Thomas
+//! - log XEB ⟨ln 2ⁿ·p(x)⟩ + γ, which like linear XEB assumes Porter-Thomas
+//!   statistics and is more sensitive near full fidelity
+//! - cross-entropy difference (Boixo et al. 2018), the sample cross entropy
+//!   placed between those of uniform and of ideal sampling on the exact
+//!   distribution, so it is 0 and 1 for them on any circuit
+//!
+//! Zero ideal probabilities would make the logarithmic estimators diverge,
+//! so probabilities are raised to [`LOG_FLOOR`]·2⁻ⁿ before taking logs.
+
+use crate::stats;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Euler-Mascheroni constant γ
+pub const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+
+/// Smallest ideal probability, relative to 2⁻ⁿ, that enters a logarithm
+pub const LOG_FLOOR: f64 = 1e-6;
+
+/// Fidelity estimator computed from the ideal probabilities of the samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Estimator {
+    /// Linear XEB 2ⁿ·⟨p(x)⟩ - 1
+    #[default]
+    Linear,
+    /// Linear XEB normalized to exactly 1 for ideal sampling
+    Normalized,
+    /// Logarithmic XEB ⟨ln 2ⁿ·p(x)⟩ + γ
+    Log,
+    /// Cross-entropy difference normalized by the exact distribution
+    CrossEntropyDifference,
+}
+
+impl Estimator {
+    /// All estimators, in the order they are listed to users
+    pub const ALL: [Estimator; 4] =
+        [Estimator::Linear, Estimator::Normalized, Estimator::Log, Estimator::CrossEntropyDifference];
+
+    /// Short name used on the command line and in results
+    pub fn name(&self) -> &'static str {
+        match self {
+            Estimator::Linear => "linear",
+            Estimator::Normalized => "normalized",
+            Estimator::Log => "log",
+            Estimator::CrossEntropyDifference => "cross-entropy-difference",
+        }
+    }
+
+    /// Look up an estimator by its name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.name() == name)
+    }
+
+    /// Whether the estimator needs the full ideal distribution, not only
+    /// the probabilities of the samples
+    pub fn needs_distribution(&self) -> bool {
+        matches!(self, Estimator::Normalized | Estimator::CrossEntropyDifference)
+    }
+
+    /// Per-shot terms of the estimator on `n_qubits`, from the ideal
+    /// probabilities of the samples
+    ///
+    /// `ideal` is the full ideal distribution; estimators that need it
+    /// return `None` without it, and also for a uniform ideal distribution,
+    /// which they cannot tell apart from noise.
+    pub fn terms(&self, sample_probs: &[f64], n_qubits: usize, ideal: Option<&[f64]>) -> Option<ShotTerms> {
+        let dim = 2f64.powi(n_qubits as i32);
+        let floor = LOG_FLOOR / dim;
+        let ln = |p: f64| p.max(floor).ln();
+        let terms = match self {
+            Estimator::Linear => ShotTerms::new(sample_probs.iter().map(|p| dim * p).collect(), 1.0, -1.0),
+            Estimator::Normalized => {
+                // Linear XEB of ideal sampling, 2ⁿ·Σp² - 1
+                let ideal_xeb = dim * ideal?.iter().map(|p| p * p).sum::<f64>() - 1.0;
+                if ideal_xeb < 1e-9 {
+                    return None;
+                }
+                ShotTerms::new(sample_probs.iter().map(|p| dim * p).collect(), 1.0 / ideal_xeb, -1.0 / ideal_xeb)
+            }
+            Estimator::Log => ShotTerms::new(sample_probs.iter().map(|&p| ln(p) + dim.ln()).collect(), 1.0, EULER_GAMMA),
+            Estimator::CrossEntropyDifference => {
+                let ideal = ideal?;
+                // Cross entropies of uniform and of ideal sampling against `ideal`
+                let uniform = -ideal.iter().map(|&p| ln(p)).sum::<f64>() / dim;
+                let entropy = -ideal.iter().map(|&p| p * ln(p)).sum::<f64>();
+                // (H_u - CE)/(H_u - H) with CE = -⟨ln p(x)⟩
+                let span = uniform - entropy;
+                if span < 1e-9 {
+                    return None;
+                }
+                ShotTerms::new(sample_probs.iter().map(|&p| ln(p)).collect(), 1.0 / span, uniform / span)
+            }
+        };
+        Some(terms)
+    }
+}
+
+/// Value of an estimator and its standard error
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Estimate {
+    pub value: f64,
+    /// `None` for fewer than two shots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub std_error: Option<f64>,
+}
+
+/// How the terms of [`ShotTerms`] relate to the shots
+#[derive(Debug, Clone)]
+enum Grouping {
+    /// One term per independent shot
+    Independent,
+    /// Consecutive clusters of correlated shots of the given sizes
+    Clustered(Vec<usize>),
+    /// Each term stands for the given number of identical shots
+    Counted(Vec<u64>),
+}
+
+/// Per-shot terms t(x) of an estimator scale·⟨t⟩ + offset
+#[derive(Debug, Clone)]
+pub struct ShotTerms {
+    terms: Vec<f64>,
+    scale: f64,
+    offset: f64,
+    grouping: Grouping,
+}
+
+impl ShotTerms {
+    fn new(terms: Vec<f64>, scale: f64, offset: f64) -> Self {
+        Self { terms, scale, offset, grouping: Grouping::Independent }
+    }
+
+    /// Treat the shots as consecutive clusters of the given sizes, such as
+    /// the shots of each noisy trajectory
+    pub fn clustered(self, clusters: Vec<usize>) -> Self {
+        Self { grouping: Grouping::Clustered(clusters), ..self }
+    }
+
+    /// Treat term i as `counts[i]` identical shots, as in a histogram of
+    /// measured outcomes
+    pub fn counted(self, counts: Vec<u64>) -> Self {
+        assert_eq!(counts.len(), self.terms.len(), "one count per term");
+        Self { grouping: Grouping::Counted(counts), ..self }
+    }
+
+    /// Estimate and its standard error from the sample variance of the terms,
+    /// or of the cluster sums for clustered shots
+    pub fn estimate(&self) -> Estimate {
+        let (mean, error) = match &self.grouping {
+            Grouping::Independent => (
+                self.terms.iter().sum::<f64>() / self.terms.len() as f64,
+                stats::mean_with_error(&self.terms),
+            ),
+            Grouping::Clustered(clusters) => (
+                self.terms.iter().sum::<f64>() / self.terms.len() as f64,
+                stats::clustered_mean_with_error(&self.terms, clusters),
+            ),
+            Grouping::Counted(counts) => (
+                self.terms.iter().zip(counts).map(|(t, &c)| t * c as f64).sum::<f64>()
+                    / counts.iter().sum::<u64>() as f64,
+                stats::weighted_mean_with_error(&self.terms, counts),
+            ),
+        };
+        Estimate {
+            value: self.scale * mean + self.offset,
+            std_error: error.map(|(_, error)| self.scale.abs() * error),
+        }
+    }
+
+    /// Value of the estimator for a distribution over the outcomes the terms
+    /// were computed for, such as a readout-mitigated quasi-distribution
+    pub fn weighted_value(&self, weights: &[f64]) -> f64 {
+        assert_eq!(weights.len(), self.terms.len(), "one weight per term");
+        self.scale * self.terms.iter().zip(weights).map(|(t, w)| t * w).sum::<f64>() + self.offset
+    }
+
+    /// Percentile bootstrap interval of the estimate at `confidence`,
+    /// resampling whole clusters for clustered shots
+    pub fn bootstrap_ci<R: Rng + ?Sized>(&self, n_resamples: usize, confidence: f64, rng: &mut R) -> [f64; 2] {
+        let interval = match &self.grouping {
+            Grouping::Independent => stats::bootstrap_mean_ci(&self.terms, n_resamples, confidence, rng),
+            Grouping::Clustered(clusters) => {
+                stats::bootstrap_clustered_mean_ci(&self.terms, clusters, n_resamples, confidence, rng)
+            }
+            Grouping::Counted(counts) => {
+                stats::bootstrap_weighted_mean_ci(&self.terms, counts, n_resamples, confidence, rng)
+            }
+        };
+        let [lo, hi] = interval.map(|m| self.scale * m + self.offset);
+        [lo.min(hi), lo.max(hi)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cross_entropy_difference_exact_limits() {
+        // Ideal sampling of [1/2, 1/2, 0, 0] hits both allowed outcomes equally,
+        // uniform sampling hits all four
+        let ideal = [0.5, 0.5, 0.0, 0.0];
+        let ced = Estimator::CrossEntropyDifference;
+        let from_ideal = ced.terms(&[0.5, 0.5], 2, Some(&ideal)).unwrap().estimate();
+        let from_uniform = ced.terms(&ideal, 2, Some(&ideal)).unwrap().estimate();
+        assert!((from_ideal.value - 1.0).abs() < 1e-12);
+        assert!(from_uniform.value.abs() < 1e-12);
+        assert!(ced.terms(&[0.5], 2, None).is_none());
+        assert!(ced.terms(&[0.25], 2, Some(&[0.25; 4])).is_none());
+    }
+
+    #[test]
+    fn test_normalized_xeb_exact_limits() {
+        // The README's worked example: ideal sampling scores 0.5 in linear XEB
+        let ideal = [0.45, 0.05, 0.10, 0.40];
+        let counts = [450, 50, 100, 400];
+        let shots: Vec<f64> = ideal.iter().zip(counts).flat_map(|(&p, n)| vec![p; n]).collect();
+        let linear = Estimator::Linear.terms(&shots, 2, None).unwrap().estimate();
+        let normalized = Estimator::Normalized.terms(&shots, 2, Some(&ideal)).unwrap().estimate();
+        assert!((linear.value - 0.5).abs() < 1e-12);
+        assert!((normalized.value - 1.0).abs() < 1e-12);
+        assert!((normalized.std_error.unwrap() - 2.0 * linear.std_error.unwrap()).abs() < 1e-12);
+
+        // One shot of every outcome is uniform sampling
+        let uniform = Estimator::Normalized.terms(&ideal, 2, Some(&ideal)).unwrap().estimate();
+        assert!(uniform.value.abs() < 1e-12);
+        assert!(Estimator::Normalized.terms(&[0.25], 2, Some(&[0.25; 4])).is_none());
+    }
+
+    #[test]
+    fn test_zero_probabilities_stay_finite() {
+        for estimator in Estimator::ALL {
+            let estimate = estimator.terms(&[0.0, 0.5, 0.0], 2, Some(&[0.5, 0.3, 0.2, 0.0])).unwrap().estimate();
+            assert!(estimate.value.is_finite() && estimate.std_error.unwrap().is_finite());
+        }
+        // A zero-probability shot costs ln(LOG_FLOOR) in log XEB
+        let log = Estimator::Log.terms(&[0.0], 3, None).unwrap().estimate();
+        assert!((log.value - (LOG_FLOOR.ln() + EULER_GAMMA)).abs() < 1e-12);
+        assert_eq!(Estimator::from_name("log"), Some(Estimator::Log));
+    }
+}